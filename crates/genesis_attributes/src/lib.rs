@@ -6,48 +6,124 @@ use bevy_ecs::{
     prelude::{Bundle, Component, Resource},
     reflect::ReflectComponent,
 };
-use bevy_reflect::Reflect;
+use bevy_reflect::{FromReflect, Reflect};
 use bevy_trait_query::RegisterExt;
 use derive_more::Deref;
-use genesis_config as config;
+use genesis_config::{
+    self as config,
+    TradeOffCurve::{self, Exponential, Inverse, Linear},
+};
 use genesis_derive::AttributeDisplay;
 use genesis_newtype::Probability;
 use genesis_traits::AttributeDisplay;
 use ndarray::Array;
 use rand::{seq::IteratorRandom, Rng, RngCore};
+use rand_distr::StandardNormal;
 use serde::{Deserialize, Serialize};
 use thiserror::Error;
 
-#[derive(Debug, Clone, Deref)]
-pub struct Chromosome(Vec<f32>);
+/// Values within this distance of a continuous chromosome's bounds are still valid, so saved
+/// values survive float rounding.
+const CONTINUOUS_TOLERANCE: f32 = 1e-4;
+
+#[derive(Debug, Clone)]
+enum Alleles {
+    Discrete(Vec<f32>),
+    Continuous { lower: f32, upper: f32, sigma: f32 },
+}
+
+#[derive(Debug, Clone)]
+pub struct Chromosome {
+    alleles: Alleles,
+    mutation_rate: Option<Probability>,
+    step_size: usize,
+}
 
 impl Chromosome {
     pub fn new(lower: f32, upper: f32, steps: usize) -> Self {
         let array = Array::linspace(lower, upper, steps);
-        Self(array.to_vec())
+        Self {
+            alleles: Alleles::Discrete(array.to_vec()),
+            mutation_rate: None,
+            step_size: 1,
+        }
+    }
+
+    /// A chromosome taking any value between `lower` and `upper`, mutated by adding noise with
+    /// a standard deviation of `sigma` times the range.
+    pub const fn continuous(lower: f32, upper: f32, sigma: f32) -> Self {
+        Self {
+            alleles: Alleles::Continuous {
+                lower,
+                upper,
+                sigma,
+            },
+            mutation_rate: None,
+            step_size: 1,
+        }
+    }
+
+    pub fn from_config(chromosome_config: &config::ChromosomeConfig) -> Self {
+        let mut chromosome = match chromosome_config.kind {
+            config::ChromosomeKind::Discrete => Self::new(
+                chromosome_config.min,
+                chromosome_config.max,
+                chromosome_config.steps,
+            ),
+            config::ChromosomeKind::Continuous => Self::continuous(
+                chromosome_config.min,
+                chromosome_config.max,
+                chromosome_config.sigma,
+            ),
+        };
+        chromosome.mutation_rate = chromosome_config
+            .mutation_rate
+            .and_then(|rate| Probability::new(rate).ok());
+        chromosome.step_size = chromosome_config.step_size.max(1);
+        chromosome
     }
 
     pub fn random(&self, rng: &mut dyn RngCore) -> f32 {
-        self.iter().copied().choose(rng).unwrap()
+        match &self.alleles {
+            Alleles::Discrete(values) => values.iter().copied().choose(rng).unwrap(),
+            Alleles::Continuous { lower, upper, .. } => rng.gen_range(*lower..=*upper),
+        }
     }
 
+    /// Moves up to `step_size` positions either way, or adds Gaussian noise if continuous,
+    /// using `probability` unless this chromosome has its own mutation rate.
     pub fn mutate(
         &self,
         current_value: f32,
         rng: &mut dyn RngCore,
         probability: &Probability,
     ) -> f32 {
-        if probability.as_float() >= rng.gen_range(0.0..=1.0) {
-            let max = self.len() - 1;
-            let position = self.iter().position(|&x| x == current_value).unwrap();
-            let new_position = if rng.gen_bool(0.5) {
-                position.saturating_sub(1)
-            } else {
-                (position + 1).clamp(0, max)
-            };
-            self[new_position]
-        } else {
-            current_value
+        let probability = self.mutation_rate.as_ref().unwrap_or(probability);
+        if probability.as_float() < rng.gen_range(0.0..=1.0) {
+            return current_value;
+        }
+        match &self.alleles {
+            Alleles::Discrete(values) => {
+                let max = values.len() - 1;
+                let position = nearest_position(values, current_value);
+                let step = rng.gen_range(1..=self.step_size);
+                let new_position = if rng.gen_bool(0.5) {
+                    position.saturating_sub(step)
+                } else {
+                    (position + step).clamp(0, max)
+                };
+                values[new_position]
+            }
+            Alleles::Continuous {
+                lower,
+                upper,
+                sigma,
+            } => {
+                let noise: f32 = rng.sample(StandardNormal);
+                (noise * sigma)
+                    .mul_add(self.range(), current_value)
+                    .clamp(*lower, *upper)
+            }
         }
     }
 
@@ -56,11 +132,17 @@ impl Chromosome {
     }
 
     pub fn lowest(&self) -> f32 {
-        *self.first().unwrap()
+        match &self.alleles {
+            Alleles::Discrete(values) => *values.first().unwrap(),
+            Alleles::Continuous { lower, .. } => *lower,
+        }
     }
 
     pub fn highest(&self) -> f32 {
-        *self.last().unwrap()
+        match &self.alleles {
+            Alleles::Discrete(values) => *values.last().unwrap(),
+            Alleles::Continuous { upper, .. } => *upper,
+        }
     }
 
     pub fn normalise(&self, value: f32) -> f32 {
@@ -68,97 +150,235 @@ impl Chromosome {
     }
 
     pub fn valid_value(&self, value: f32) -> bool {
-        self.iter().any(|&x| x == value)
+        match &self.alleles {
+            Alleles::Discrete(values) => values.contains(&value),
+            Alleles::Continuous { lower, upper, .. } => {
+                value >= lower - CONTINUOUS_TOLERANCE && value <= upper + CONTINUOUS_TOLERANCE
+            }
+        }
     }
 }
 
+/// The position of the value closest to `value`, so values from a config with different steps
+/// still mutate.
+fn nearest_position(values: &[f32], value: f32) -> usize {
+    values
+        .iter()
+        .enumerate()
+        .min_by(|(_, a), (_, b)| (*a - value).abs().total_cmp(&(*b - value).abs()))
+        .map_or(0, |(position, _)| position)
+}
+
 impl fmt::Display for Chromosome {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(f, "{:?}", self.0)
-    }
-}
-
-#[derive(Debug, Component, Clone, Resource)]
-pub struct Genome {
-    pub hatch_age: Chromosome,
-    pub eye_range: Chromosome,
-    pub cost_of_eating: Chromosome,
-    pub offspring_energy: Chromosome,
-    pub max_size: Chromosome,
-    pub growth_rate: Chromosome,
-    pub grab_angle: Chromosome,
-    pub food_preference: Chromosome,
-    pub base_attack: Chromosome,
-}
-
-impl Genome {
-    pub fn new() -> Self {
-        let attributes = &config::WorldConfig::global().attributes;
-        macro_rules! get_value {
-            ($attr:ident) => {
-                let (min, max, steps) = attributes.$attr;
-                let $attr = Chromosome::new(min, max, steps);
-            };
-            ($attr:ident, $($attrs:ident), +) => {
-                get_value!($attr);
-               get_value!($($attrs), +)
+        match &self.alleles {
+            Alleles::Discrete(values) => write!(f, "{values:?}"),
+            Alleles::Continuous { lower, upper, .. } => write!(f, "[{lower}, {upper}]"),
+        }
+    }
+}
+
+/// Maps `t`, between 0 and 1, onto `bounds` along `curve`.
+fn interpolate(curve: TradeOffCurve, t: f32, (min, max): (f32, f32)) -> f32 {
+    match curve {
+        Linear => t.mul_add(max - min, min),
+        Inverse => t.mul_add(min - max, max),
+        Exponential => min * (max / min).powf(t),
+    }
+}
+
+/// The constructor of a gene component, storing angles in radians.
+macro_rules! gene_new {
+    (value) => {
+        pub const fn new(value: f32) -> Self {
+            Self(value)
+        }
+    };
+    (degrees) => {
+        pub fn new(value: f32) -> Self {
+            Self(f32::to_radians(value))
+        }
+    };
+}
+
+/// The value of a gene component as written in the config.
+macro_rules! gene_value {
+    (value, $stored:expr) => {
+        $stored
+    };
+    (degrees, $stored:expr) => {
+        f32::to_degrees($stored)
+    };
+}
+
+const fn missing_gene() -> f32 {
+    f32::NAN
+}
+
+macro_rules! attributes {
+    ($($gene:ident: $component:ident($unit:ident) = [$min:expr, $max:expr, $steps:expr] in [$floor:expr, $ceil:expr]),+ $(,)?) => {
+        #[derive(Debug, Component, Clone, Resource)]
+        pub struct Genome {
+            $(pub $gene: Chromosome,)+
+            pub mutation_rate: Option<Chromosome>,
+        }
+
+        impl Genome {
+            pub fn new() -> Self {
+                let attributes = &config::WorldConfig::global().attributes;
+                Self {
+                    $($gene: Chromosome::from_config(&attributes.$gene),)+
+                    mutation_rate: attributes
+                        .mutation_rate
+                        .as_ref()
+                        .map(Chromosome::from_config),
+                }
+            }
+
+            pub fn mutate(
+                &self,
+                current_dna: Dna,
+                rng: &mut dyn RngCore,
+                probability: &Probability,
+            ) -> Dna {
+                let mut output_dna = current_dna;
+                $(output_dna.$gene = self.$gene.mutate(current_dna.$gene, rng, probability);)+
+                output_dna.mutation_rate = self
+                    .mutation_rate
+                    .as_ref()
+                    .map(|chromosome| match current_dna.mutation_rate {
+                        Some(rate) => chromosome.mutate(rate, rng, probability),
+                        None => chromosome.random(rng),
+                    });
+                output_dna
+            }
+
+            /// The chromosome of the gene called `name`, if there is one.
+            pub fn chromosome(&self, name: &str) -> Option<&Chromosome> {
+                match name {
+                    $(stringify!($gene) => Some(&self.$gene),)+
+                    "mutation_rate" => self.mutation_rate.as_ref(),
+                    _ => None,
+                }
             }
         }
-        get_value!(
-            hatch_age,
-            eye_range,
-            cost_of_eating,
-            offspring_energy,
-            max_size,
-            growth_rate,
-            grab_angle,
-            food_preference,
-            base_attack
-        );
-        Self {
-            hatch_age,
-            eye_range,
-            cost_of_eating,
-            offspring_energy,
-            max_size,
-            growth_rate,
-            grab_angle,
-            food_preference,
-            base_attack,
+
+        #[derive(Debug, Clone, Copy, Component, Serialize, Deserialize, Reflect)]
+        #[reflect(Component)]
+        pub struct Dna {
+            $(#[serde(default = "missing_gene")]
+            pub $gene: f32,)+
+            #[serde(default)]
+            pub mutation_rate: Option<f32>,
         }
-    }
 
-    pub fn mutate(
-        &self,
-        current_dna: Dna,
-        rng: &mut dyn RngCore,
-        probability: &Probability,
-    ) -> Dna {
-        let mut output_dna = current_dna;
-        macro_rules! mutate_value {
-            ($attr:ident) => {
-                output_dna.$attr = self.$attr.mutate(current_dna.$attr, rng, probability);
-            };
-            ($attr:ident, $($attrs:ident), +) => {
-                mutate_value!($attr);
-                mutate_value!($($attrs), +)
+        /// Every gene missing, so DNA loaded from before a gene existed can be filled in.
+        impl Default for Dna {
+            fn default() -> Self {
+                Self {
+                    $($gene: missing_gene(),)+
+                    mutation_rate: None,
+                }
             }
         }
-        mutate_value!(
-            hatch_age,
-            eye_range,
-            cost_of_eating,
-            offspring_energy,
-            max_size,
-            growth_rate,
-            grab_angle,
-            food_preference,
-            base_attack
-        );
-        output_dna
-    }
+
+        impl Dna {
+            pub fn new(genome: &Genome, rng: &mut dyn RngCore) -> Self {
+                Self {
+                    $($gene: genome.$gene.random(rng),)+
+                    mutation_rate: genome
+                        .mutation_rate
+                        .as_ref()
+                        .map(|chromosome| chromosome.random(rng)),
+                }
+            }
+
+            /// Draws a random value for every missing gene, returning whether any were missing.
+            pub fn fill_missing(&mut self, genome: &Genome, rng: &mut dyn RngCore) -> bool {
+                let missing = [$(self.$gene.is_nan()),+].contains(&true);
+                $(if self.$gene.is_nan() {
+                    self.$gene = genome.$gene.random(rng);
+                })+
+                missing
+            }
+
+            /// The value of the gene called `name`, if there is one.
+            pub fn gene(&self, name: &str) -> Option<f32> {
+                match name {
+                    $(stringify!($gene) => Some(self.$gene),)+
+                    "mutation_rate" => self.mutation_rate,
+                    _ => None,
+                }
+            }
+
+            pub fn validate(&self, genome: &Genome) -> Result<(), DnaValidationError> {
+                $(if !genome.$gene.valid_value(self.$gene) {
+                    return Err(DnaValidationError::InvalidValue(
+                        self.$gene,
+                        stringify!($gene).to_string(),
+                        genome.$gene.clone(),
+                    ));
+                })+
+                if let (Some(chromosome), Some(rate)) = (&genome.mutation_rate, self.mutation_rate) {
+                    if !chromosome.valid_value(rate) {
+                        return Err(DnaValidationError::InvalidValue(
+                            rate,
+                            "mutation_rate".to_string(),
+                            chromosome.clone(),
+                        ));
+                    }
+                }
+                Ok(())
+            }
+        }
+
+        $(
+            #[derive(Component, Debug, Deref, Default, Reflect)]
+            #[reflect(Component)]
+            pub struct $component(f32);
+
+            impl $component {
+                gene_new!($unit);
+            }
+
+            impl AttributeDisplay for $component {
+                fn name(&self) -> &str {
+                    stringify!($component)
+                }
+
+                fn value(&self) -> f32 {
+                    gene_value!($unit, self.0)
+                }
+
+                fn display(&self) -> String {
+                    format!("{}: {:.3}", self.name(), self.value())
+                }
+            }
+        )+
+
+        /// A component for every gene.
+        #[derive(Bundle, Debug)]
+        pub struct GeneBundle {
+            $(pub $gene: $component,)+
+        }
+
+        impl GeneBundle {
+            pub fn new(dna: &Dna) -> Self {
+                Self {
+                    $($gene: $component::new(dna.$gene),)+
+                }
+            }
+        }
+
+        fn register_genes(app: &mut bevy_app::App) {
+            $(app.register_type::<$component>()
+                .register_component_as::<dyn AttributeDisplay, $component>();)+
+        }
+    };
 }
 
+config::genes!(attributes);
+
 impl Default for Genome {
     fn default() -> Self {
         Self::new()
@@ -171,73 +391,24 @@ pub enum DnaValidationError {
     InvalidValue(f32, String, Chromosome),
 }
 
-#[derive(Debug, Clone, Copy, Component, Serialize, Deserialize, Reflect, Default)]
-#[reflect(Component)]
-pub struct Dna {
-    pub hatch_age: f32,
-    pub eye_range: f32,
-    pub cost_of_eating: f32,
-    pub offspring_energy: f32,
-    pub max_size: f32,
-    pub growth_rate: f32,
-    pub grab_angle: f32,
-    pub food_preference: f32,
-    pub base_attack: f32,
-}
-
-impl Dna {
-    pub fn new(genome: &Genome, rng: &mut dyn RngCore) -> Self {
-        Self {
-            hatch_age: genome.hatch_age.random(rng),
-            eye_range: genome.eye_range.random(rng),
-            cost_of_eating: genome.cost_of_eating.random(rng),
-            offspring_energy: genome.offspring_energy.random(rng),
-            max_size: genome.max_size.random(rng),
-            growth_rate: genome.growth_rate.random(rng),
-            grab_angle: genome.grab_angle.random(rng),
-            food_preference: genome.food_preference.random(rng),
-            base_attack: genome.base_attack.random(rng),
-        }
-    }
-
-    pub fn validate(&self, genome: &Genome) -> Result<(), DnaValidationError> {
-        macro_rules! validate_values {
-            ($attr:ident) => {
-                if !genome.$attr.valid_value(self.$attr) {
-                    return Err(DnaValidationError::InvalidValue(
-                        self.$attr,
-                        stringify!($attr).to_string(),
-                        genome.$attr.clone(),
-                    ))
-                }
-            };
-            ($attr:ident, $($attrs:ident), +) => {
-                validate_values!($attr);
-                validate_values!($($attrs), +)
-            }
-        }
-        validate_values!(
-            hatch_age,
-            eye_range,
-            cost_of_eating,
-            offspring_energy,
-            max_size,
-            growth_rate,
-            grab_angle,
-            food_preference
-        );
-        Ok(())
-    }
+/// The value of a trade-off for a bug, if it has the source gene.
+fn trade_off_value(trade_off: &config::TradeOffConfig, dna: &Dna, genome: &Genome) -> Option<f32> {
+    let chromosome = genome.chromosome(&trade_off.source)?;
+    let gene = dna.gene(&trade_off.source)?;
+    Some(interpolate(
+        trade_off.curve,
+        chromosome.normalise(gene),
+        trade_off.bounds,
+    ))
 }
 
-#[derive(Component, Debug, Deref, AttributeDisplay, Default, Reflect)]
-#[reflect(Component)]
-pub struct HatchAge(f32);
-
-impl HatchAge {
-    pub const fn new(value: f32) -> Self {
-        Self(value)
-    }
+/// The value of one of the dependent attributes in `dependent_attributes.trade_offs`.
+fn dependent_value(name: &str, dna: &Dna, genome: &Genome) -> f32 {
+    let trade_off = config::WorldConfig::global()
+        .dependent_attributes
+        .trade_off(name);
+    trade_off_value(trade_off, dna, genome)
+        .expect("Dependent attributes have a gene every bug has as their source.")
 }
 
 #[derive(Component, Debug, Deref, AttributeDisplay, Default, Reflect)]
@@ -245,15 +416,8 @@ impl HatchAge {
 pub struct AdultAge(f32);
 
 impl AdultAge {
-    pub fn new(hatch_age: f32, ha_chromosome: &Chromosome) -> Self {
-        let (aa_min, aa_max) = config::WorldConfig::global()
-            .dependent_attributes
-            .adult_age_bounds;
-        let aa_range = aa_max - aa_min;
-        let value = ha_chromosome
-            .normalise(hatch_age)
-            .mul_add(-aa_range, aa_max);
-        Self(value)
+    pub fn new(dna: &Dna, genome: &Genome) -> Self {
+        Self(dependent_value("adult_age", dna, genome))
     }
 }
 
@@ -262,23 +426,8 @@ impl AdultAge {
 pub struct DeathAge(f32);
 
 impl DeathAge {
-    pub fn new(max_size: f32, ms_chromosome: &Chromosome) -> Self {
-        let (da_min, da_max) = config::WorldConfig::global()
-            .dependent_attributes
-            .death_age_bounds;
-        let da_range = da_max - da_min;
-        let value = ms_chromosome.normalise(max_size).mul_add(da_range, da_min);
-        Self(value)
-    }
-}
-
-#[derive(Component, Debug, Deref, AttributeDisplay, Default, Reflect)]
-#[reflect(Component)]
-pub struct EyeRange(f32);
-
-impl EyeRange {
-    pub const fn new(value: f32) -> Self {
-        Self(value)
+    pub fn new(dna: &Dna, genome: &Genome) -> Self {
+        Self(dependent_value("death_age", dna, genome))
     }
 }
 
@@ -287,17 +436,8 @@ impl EyeRange {
 pub struct EyeAngle(f32);
 
 impl EyeAngle {
-    pub fn new(eye_range: f32, er_chromosome: &Chromosome) -> Self {
-        let (ea_min, ea_max) = config::WorldConfig::global()
-            .dependent_attributes
-            .eye_angle_bounds;
-        let ea_range = ea_max - ea_min;
-        let value = f32::to_radians(
-            er_chromosome
-                .normalise(eye_range)
-                .mul_add(-ea_range, ea_max),
-        );
-        Self(value)
+    pub fn new(dna: &Dna, genome: &Genome) -> Self {
+        Self(f32::to_radians(dependent_value("eye_angle", dna, genome)))
     }
 }
 
@@ -314,42 +454,13 @@ impl AttributeDisplay for EyeAngle {
     }
 }
 
-#[derive(Component, Debug, Deref, AttributeDisplay, Default, Reflect)]
-#[reflect(Component)]
-pub struct CostOfEating(f32);
-
-impl CostOfEating {
-    pub const fn new(value: f32) -> Self {
-        Self(value)
-    }
-}
-
-#[derive(Component, Debug, Deref, AttributeDisplay, Default, Reflect)]
-#[reflect(Component)]
-pub struct OffspringEnergy(f32);
-
-impl OffspringEnergy {
-    pub const fn new(value: f32) -> Self {
-        Self(value)
-    }
-}
-
 #[derive(Component, Debug, Deref, Default, Reflect)]
 #[reflect(Component)]
 pub struct MouthWidth(f32);
 
 impl MouthWidth {
-    pub fn new(cost_of_eating: f32, coe_chromosome: &Chromosome) -> Self {
-        let (mw_min, mw_max) = config::WorldConfig::global()
-            .dependent_attributes
-            .mouth_width_bounds;
-        let mw_range = mw_max - mw_min;
-        let value = f32::to_radians(
-            coe_chromosome
-                .normalise(cost_of_eating)
-                .mul_add(mw_range, mw_min),
-        );
-        Self(value)
+    pub fn new(dna: &Dna, genome: &Genome) -> Self {
+        Self(f32::to_radians(dependent_value("mouth_width", dna, genome)))
     }
 }
 
@@ -372,161 +483,115 @@ impl AttributeDisplay for MouthWidth {
 pub struct HatchSize(f32);
 
 impl HatchSize {
-    pub fn new(hatch_age: f32, ha_chromosome: &Chromosome) -> Self {
-        let (hs_min, hs_max) = config::WorldConfig::global()
-            .dependent_attributes
-            .hatch_size_bounds;
-        let hs_range = hs_max - hs_min;
-        let value = ha_chromosome.normalise(hatch_age).mul_add(hs_range, hs_min);
-        Self(value)
-    }
-}
-
-#[derive(Component, Debug, Deref, AttributeDisplay, Default, Reflect)]
-#[reflect(Component)]
-pub struct MaxSize(f32);
-
-impl MaxSize {
-    pub const fn new(value: f32) -> Self {
-        Self(value)
+    pub fn new(dna: &Dna, genome: &Genome) -> Self {
+        Self(dependent_value("hatch_size", dna, genome))
     }
 }
 
 #[derive(Component, Debug, Deref, AttributeDisplay, Default, Reflect)]
 #[reflect(Component)]
-pub struct GrowthRate(f32);
-
-impl GrowthRate {
-    pub const fn new(value: f32) -> Self {
-        Self(value)
-    }
-}
-
-#[derive(Component, Debug, Deref, Default, Reflect)]
-#[reflect(Component)]
-pub struct GrabAngle(f32);
+pub struct GrabStrength(f32);
 
-impl GrabAngle {
-    pub fn new(value: f32) -> Self {
-        let value = f32::to_radians(value);
-        Self(value)
+impl GrabStrength {
+    pub fn new(dna: &Dna, genome: &Genome) -> Self {
+        Self(dependent_value("grab_strength", dna, genome))
     }
 }
 
-impl AttributeDisplay for GrabAngle {
-    fn name(&self) -> &str {
-        "GrabAngle"
-    }
-
-    fn value(&self) -> f32 {
-        f32::to_degrees(self.0)
+impl FoodPreference {
+    pub fn plant_digestion_efficiency(&self) -> f32 {
+        (-1.0f32).mul_add(self.0, 0.8)
     }
 
-    fn display(&self) -> String {
-        format!("{}: {:.3}", self.name(), self.value())
+    pub fn meat_digestion_efficiency(&self) -> f32 {
+        0.8f32.mul_add(self.0, 0.1)
     }
 }
 
 #[derive(Component, Debug, Deref, AttributeDisplay, Default, Reflect)]
 #[reflect(Component)]
-pub struct GrabStrength(f32);
+pub struct BaseDefence(f32);
 
-impl GrabStrength {
-    pub fn new(grab_angle: f32, ga_chromosome: &Chromosome) -> Self {
-        let (gs_min, gs_max) = config::WorldConfig::global()
-            .dependent_attributes
-            .grab_strength_bounds;
-        let gs_range = gs_max - gs_min;
-        let value = ga_chromosome
-            .normalise(grab_angle)
-            .mul_add(-gs_range, gs_max);
-        Self(value)
+impl BaseDefence {
+    pub fn new(dna: &Dna, genome: &Genome) -> Self {
+        Self(dependent_value("base_defence", dna, genome))
     }
 }
 
-#[derive(Component, Debug, Deref, AttributeDisplay, Default, Reflect)]
-#[reflect(Component)]
-pub struct FoodPreference(f32);
+/// A trade-off from `dependent_attributes.trade_offs` without a component of its own.
+#[derive(Debug, Clone, Default, Reflect, FromReflect)]
+pub struct TradeOff {
+    name: String,
+    value: f32,
+}
 
-impl FoodPreference {
-    pub const fn new(value: f32) -> Self {
-        Self(value)
-    }
-    pub fn plant_digestion_efficiency(&self) -> f32 {
-        (-1.0f32).mul_add(self.0, 0.8)
+impl TradeOff {
+    pub fn name(&self) -> &str {
+        &self.name
     }
 
-    pub fn meat_digestion_efficiency(&self) -> f32 {
-        0.8f32.mul_add(self.0, 0.1)
+    pub const fn value(&self) -> f32 {
+        self.value
     }
 }
 
-#[derive(Component, Debug, Deref, AttributeDisplay, Default, Reflect)]
+/// Every extra trade-off whose source gene the bug has.
+#[derive(Component, Debug, Deref, Default, Reflect)]
 #[reflect(Component)]
-pub struct BaseAttack(f32);
+pub struct TradeOffs(Vec<TradeOff>);
 
-impl BaseAttack {
-    pub const fn new(value: f32) -> Self {
-        Self(value)
+impl TradeOffs {
+    pub fn new(dna: &Dna, genome: &Genome) -> Self {
+        let trade_offs = config::WorldConfig::global()
+            .dependent_attributes
+            .extra_trade_offs()
+            .filter_map(|(name, trade_off)| {
+                Some(TradeOff {
+                    name: name.clone(),
+                    value: trade_off_value(trade_off, dna, genome)?,
+                })
+            })
+            .collect();
+        Self(trade_offs)
     }
 }
 
+/// Only present on bugs when the mutation rate is heritable.
 #[derive(Component, Debug, Deref, AttributeDisplay, Default, Reflect)]
 #[reflect(Component)]
-pub struct BaseDefence(f32);
+pub struct MutationRate(f32);
 
-impl BaseDefence {
-    pub fn new(base_attack: f32, ba_chromosome: &Chromosome) -> Self {
-        let (bd_min, bd_max) = config::WorldConfig::global()
-            .dependent_attributes
-            .base_defence_bounds;
-        let bd_range = bd_max - bd_min;
-        let value = ba_chromosome
-            .normalise(base_attack)
-            .mul_add(-bd_range, bd_max);
+impl MutationRate {
+    pub const fn new(value: f32) -> Self {
         Self(value)
     }
 }
 
 #[derive(Bundle, Debug)]
 pub struct AttributeBundle {
-    pub hatch_age: HatchAge,
+    pub genes: GeneBundle,
     pub adult_age: AdultAge,
     pub death_age: DeathAge,
-    pub eye_range: EyeRange,
     pub eye_angle: EyeAngle,
-    pub cost_of_eating: CostOfEating,
-    pub offspring_energy: OffspringEnergy,
     pub mouth_width: MouthWidth,
     pub hatch_size: HatchSize,
-    pub max_size: MaxSize,
-    pub growth_rate: GrowthRate,
-    pub grab_angle: GrabAngle,
     pub grab_strength: GrabStrength,
-    pub food_preference: FoodPreference,
-    pub base_attack: BaseAttack,
     pub base_defence: BaseDefence,
+    pub trade_offs: TradeOffs,
 }
 
 impl AttributeBundle {
     pub fn new(dna: &Dna, genome: &Genome) -> Self {
         Self {
-            hatch_age: HatchAge::new(dna.hatch_age),
-            adult_age: AdultAge::new(dna.hatch_age, &genome.hatch_age),
-            death_age: DeathAge::new(dna.max_size, &genome.max_size),
-            eye_range: EyeRange::new(dna.eye_range),
-            eye_angle: EyeAngle::new(dna.eye_range, &genome.eye_range),
-            cost_of_eating: CostOfEating::new(dna.cost_of_eating),
-            offspring_energy: OffspringEnergy::new(dna.offspring_energy),
-            mouth_width: MouthWidth::new(dna.cost_of_eating, &genome.cost_of_eating),
-            hatch_size: HatchSize::new(dna.hatch_age, &genome.hatch_age),
-            max_size: MaxSize::new(dna.max_size),
-            growth_rate: GrowthRate::new(dna.growth_rate),
-            grab_angle: GrabAngle::new(dna.grab_angle),
-            grab_strength: GrabStrength::new(dna.grab_angle, &genome.grab_angle),
-            food_preference: FoodPreference::new(dna.food_preference),
-            base_attack: BaseAttack::new(dna.base_attack),
-            base_defence: BaseDefence::new(dna.base_attack, &genome.base_attack),
+            genes: GeneBundle::new(dna),
+            adult_age: AdultAge::new(dna, genome),
+            death_age: DeathAge::new(dna, genome),
+            eye_angle: EyeAngle::new(dna, genome),
+            mouth_width: MouthWidth::new(dna, genome),
+            hatch_size: HatchSize::new(dna, genome),
+            grab_strength: GrabStrength::new(dna, genome),
+            base_defence: BaseDefence::new(dna, genome),
+            trade_offs: TradeOffs::new(dna, genome),
         }
     }
 }
@@ -535,38 +600,196 @@ pub struct AttributesPlugin;
 
 impl Plugin for AttributesPlugin {
     fn build(&self, app: &mut bevy_app::App) {
+        register_genes(app);
         app.register_type::<Dna>()
-            .register_type::<HatchAge>()
+            .register_type::<Option<f32>>()
             .register_type::<AdultAge>()
             .register_type::<DeathAge>()
-            .register_type::<EyeRange>()
             .register_type::<EyeAngle>()
-            .register_type::<CostOfEating>()
-            .register_type::<OffspringEnergy>()
             .register_type::<MouthWidth>()
             .register_type::<HatchSize>()
-            .register_type::<MaxSize>()
-            .register_type::<GrowthRate>()
-            .register_type::<GrabAngle>()
             .register_type::<GrabStrength>()
-            .register_type::<FoodPreference>()
-            .register_type::<BaseAttack>()
             .register_type::<BaseDefence>()
-            .register_component_as::<dyn AttributeDisplay, HatchAge>()
+            .register_type::<MutationRate>()
+            .register_type::<TradeOff>()
+            .register_type::<Vec<TradeOff>>()
+            .register_type::<TradeOffs>()
             .register_component_as::<dyn AttributeDisplay, AdultAge>()
             .register_component_as::<dyn AttributeDisplay, DeathAge>()
-            .register_component_as::<dyn AttributeDisplay, EyeRange>()
             .register_component_as::<dyn AttributeDisplay, EyeAngle>()
-            .register_component_as::<dyn AttributeDisplay, CostOfEating>()
-            .register_component_as::<dyn AttributeDisplay, OffspringEnergy>()
             .register_component_as::<dyn AttributeDisplay, MouthWidth>()
             .register_component_as::<dyn AttributeDisplay, HatchSize>()
-            .register_component_as::<dyn AttributeDisplay, MaxSize>()
-            .register_component_as::<dyn AttributeDisplay, GrowthRate>()
-            .register_component_as::<dyn AttributeDisplay, GrabAngle>()
             .register_component_as::<dyn AttributeDisplay, GrabStrength>()
-            .register_component_as::<dyn AttributeDisplay, FoodPreference>()
-            .register_component_as::<dyn AttributeDisplay, BaseAttack>()
-            .register_component_as::<dyn AttributeDisplay, BaseDefence>();
+            .register_component_as::<dyn AttributeDisplay, BaseDefence>()
+            .register_component_as::<dyn AttributeDisplay, MutationRate>();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::{rngs::StdRng, SeedableRng};
+
+    use super::*;
+
+    fn genome() -> Genome {
+        config::initialize_configs(Some(config::WorldConfig::default()));
+        Genome::new()
+    }
+
+    fn always() -> Probability {
+        Probability::new(1.0).unwrap()
+    }
+
+    #[test]
+    fn continuous_mutation_stays_within_bounds() {
+        let mut rng = StdRng::seed_from_u64(2);
+        let chromosome = Chromosome::continuous(0.0, 1.0, 0.1);
+        let mut value = 0.95;
+        for _ in 0..1000 {
+            let mutated = chromosome.mutate(value, &mut rng, &always());
+            assert!((0.0..=1.0).contains(&mutated));
+            value = mutated;
+        }
+        assert_eq!(
+            chromosome.mutate(0.5, &mut rng, &Probability::new(0.0).unwrap()),
+            0.5
+        );
+    }
+
+    #[test]
+    fn wide_continuous_mutation_is_clamped_to_the_bounds() {
+        let mut rng = StdRng::seed_from_u64(2);
+        let chromosome = Chromosome::continuous(-1.0, 1.0, 100.0);
+        let mutated: Vec<f32> = (0..100)
+            .map(|_| chromosome.mutate(0.0, &mut rng, &always()))
+            .collect();
+        assert!(mutated.iter().all(|value| [-1.0, 1.0].contains(value)));
+        assert!(mutated.contains(&-1.0) && mutated.contains(&1.0));
+    }
+
+    #[test]
+    fn continuous_values_are_valid_within_the_tolerance() {
+        let chromosome = Chromosome::continuous(0.0, 1.0, 0.1);
+        assert!(chromosome.valid_value(0.37));
+        assert!(chromosome.valid_value(-CONTINUOUS_TOLERANCE / 2.0));
+        assert!(chromosome.valid_value(1.0 + CONTINUOUS_TOLERANCE / 2.0));
+        assert!(!chromosome.valid_value(-CONTINUOUS_TOLERANCE * 2.0));
+        assert!(!chromosome.valid_value(1.0 + CONTINUOUS_TOLERANCE * 2.0));
+
+        let discrete = Chromosome::new(0.0, 1.0, 5);
+        assert!(discrete.valid_value(0.25));
+        assert!(!discrete.valid_value(0.25 + CONTINUOUS_TOLERANCE / 2.0));
+    }
+
+    #[test]
+    fn nearest_position_picks_the_closest_value() {
+        let values = [0.0, 0.5, 1.0];
+        assert_eq!(nearest_position(&values, 0.2), 0);
+        assert_eq!(nearest_position(&values, 0.3), 1);
+        assert_eq!(nearest_position(&values, 0.5), 1);
+        assert_eq!(nearest_position(&values, 7.0), 2);
+        assert_eq!(nearest_position(&values, -7.0), 0);
+    }
+
+    #[test]
+    fn discrete_mutation_moves_at_most_the_step_size() {
+        let mut rng = StdRng::seed_from_u64(2);
+        let mut config = config::ChromosomeConfig::new(0.0, 1.0, 9);
+        config.step_size = 2;
+        let chromosome = Chromosome::from_config(&config);
+
+        let mutated: Vec<f32> = (0..200)
+            .map(|_| chromosome.mutate(0.5, &mut rng, &always()))
+            .collect();
+        for step in [0.25, 0.375, 0.625, 0.75] {
+            assert!(mutated.contains(&step));
+        }
+        assert!(mutated.iter().all(|value| (value - 0.5).abs() <= 0.25));
+
+        // A value off the grid moves from the closest allele.
+        let mutated: Vec<f32> = (0..200)
+            .map(|_| chromosome.mutate(0.05, &mut rng, &always()))
+            .collect();
+        assert!(mutated.iter().all(|value| *value <= 0.25));
+        assert!(mutated.iter().all(|value| chromosome.valid_value(*value)));
+    }
+
+    #[test]
+    fn trade_off_curves_meet_their_bounds() {
+        let bounds = (1.0, 4.0);
+        assert_eq!(interpolate(Linear, 0.0, bounds), 1.0);
+        assert_eq!(interpolate(Linear, 0.5, bounds), 2.5);
+        assert_eq!(interpolate(Linear, 1.0, bounds), 4.0);
+        assert_eq!(interpolate(Inverse, 0.0, bounds), 4.0);
+        assert_eq!(interpolate(Inverse, 0.5, bounds), 2.5);
+        assert_eq!(interpolate(Inverse, 1.0, bounds), 1.0);
+        assert_eq!(interpolate(Exponential, 0.0, bounds), 1.0);
+        assert_eq!(interpolate(Exponential, 0.5, bounds), 2.0);
+        assert_eq!(interpolate(Exponential, 1.0, bounds), 4.0);
+    }
+
+    #[test]
+    fn untagged_chromosome_configs_load() {
+        let list: config::ChromosomeConfig = serde_json::from_str("[0.0, 2.0, 5]").unwrap();
+        assert_eq!(list, config::ChromosomeConfig::new(0.0, 2.0, 5));
+        let discrete = Chromosome::from_config(&list);
+        assert!(discrete.valid_value(1.5));
+        assert!(!discrete.valid_value(1.3));
+
+        let table: config::ChromosomeConfig = serde_json::from_str(
+            r#"{"min": 0.0, "max": 2.0, "steps": 5, "kind": "continuous", "sigma": 0.1,
+                "mutation_rate": 0.0}"#,
+        )
+        .unwrap();
+        assert_eq!(table.kind, config::ChromosomeKind::Continuous);
+        assert_eq!(table.step_size, 1);
+        let continuous = Chromosome::from_config(&table);
+        assert!(continuous.valid_value(1.3));
+        assert_eq!((continuous.lowest(), continuous.highest()), (0.0, 2.0));
+        // Its own rate overrides the one given.
+        let mut rng = StdRng::seed_from_u64(2);
+        assert_eq!(continuous.mutate(1.3, &mut rng, &always()), 1.3);
+    }
+
+    #[test]
+    fn dna_has_every_gene_in_the_table() {
+        let genome = genome();
+        let dna = Dna::new(&genome, &mut StdRng::seed_from_u64(2));
+        for name in config::GENES {
+            let chromosome = genome.chromosome(name);
+            let gene = dna.gene(name);
+            assert_eq!(chromosome.is_some(), gene.is_some(), "{name}");
+            if *name != "mutation_rate" {
+                assert!(chromosome.unwrap().valid_value(gene.unwrap()), "{name}");
+            }
+        }
+        assert!(genome.chromosome("not_a_gene").is_none());
+        assert_eq!(dna.gene("not_a_gene"), None);
+        assert!(dna.validate(&genome).is_ok());
+        let mut filled = dna;
+        assert!(!filled.fill_missing(&genome, &mut StdRng::seed_from_u64(2)));
+    }
+
+    #[test]
+    fn missing_genes_are_filled_before_use() {
+        let genome = genome();
+        let mut rng = StdRng::seed_from_u64(2);
+        let mut saved = serde_json::to_value(Dna::new(&genome, &mut rng)).unwrap();
+        let saved_genes = saved.as_object_mut().unwrap();
+        saved_genes.remove("venom");
+        saved_genes.remove("armour");
+        let mut dna: Dna = serde_json::from_value(saved).unwrap();
+        assert!(dna.venom.is_nan() && dna.armour.is_nan());
+        assert!(dna.validate(&genome).is_err());
+        let max_size = dna.max_size;
+
+        assert!(dna.fill_missing(&genome, &mut rng));
+
+        assert!(genome.venom.valid_value(dna.venom));
+        assert!(genome.armour.valid_value(dna.armour));
+        assert_eq!(dna.max_size, max_size);
+        assert!(dna.validate(&genome).is_ok());
+        assert!(!dna.fill_missing(&genome, &mut rng));
+        assert!(Dna::default().fill_missing(&genome, &mut rng));
     }
 }
@@ -17,6 +17,9 @@ pub enum BrainError {
     #[error("This neuron cannot be remove")]
     NeuronRemovalError,
 
+    #[error("Input and output neurons cannot be removed.")]
+    IoNeuronRemovalError,
+
     #[error("Input array is of incorrect length")]
     InputArrayError,
 }
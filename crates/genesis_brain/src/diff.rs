@@ -0,0 +1,212 @@
+use std::fmt;
+
+use bevy_reflect::{FromReflect, Reflect};
+use serde::{Deserialize, Serialize};
+
+use crate::{activation::ActivationFunctionKind, Brain};
+
+/// A single difference between two brains, as seen going from the first to the second.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, Reflect, FromReflect)]
+pub enum BrainChange {
+    AddedNeuron {
+        neuron: usize,
+    },
+    ChangedBias {
+        neuron: usize,
+        delta: f32,
+    },
+    ChangedActivation {
+        neuron: usize,
+        activation: ActivationFunctionKind,
+    },
+    AddedSynapse {
+        from: usize,
+        to: usize,
+    },
+    DeactivatedSynapse {
+        from: usize,
+        to: usize,
+    },
+    ChangedWeight {
+        from: usize,
+        to: usize,
+        delta: f32,
+    },
+}
+
+impl BrainChange {
+    /// The neuron this change affects, if it is a neuron change.
+    #[must_use]
+    pub const fn neuron(&self) -> Option<usize> {
+        match self {
+            Self::AddedNeuron { neuron }
+            | Self::ChangedBias { neuron, .. }
+            | Self::ChangedActivation { neuron, .. } => Some(*neuron),
+            _ => None,
+        }
+    }
+
+    /// The `(from, to)` pair of the synapse this change affects, if it is a synapse change.
+    #[must_use]
+    pub const fn synapse(&self) -> Option<(usize, usize)> {
+        match self {
+            Self::AddedSynapse { from, to }
+            | Self::DeactivatedSynapse { from, to }
+            | Self::ChangedWeight { from, to, .. } => Some((*from, *to)),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for BrainChange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::AddedNeuron { neuron } => write!(f, "Added neuron {neuron}"),
+            Self::ChangedBias { neuron, delta } => {
+                write!(f, "Changed bias of neuron {neuron} by {delta:+.2}")
+            }
+            Self::ChangedActivation { neuron, activation } => write!(
+                f,
+                "Changed activation of neuron {neuron} to {}",
+                activation.display()
+            ),
+            Self::AddedSynapse { from, to } => write!(f, "Added synapse {from} -> {to}"),
+            Self::DeactivatedSynapse { from, to } => {
+                write!(f, "Deactivated synapse {from} -> {to}")
+            }
+            Self::ChangedWeight { from, to, delta } => {
+                write!(f, "Changed weight of synapse {from} -> {to} by {delta:+.2}")
+            }
+        }
+    }
+}
+
+/// Every change needed to turn one brain into another.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, Reflect, FromReflect)]
+pub struct BrainDiff {
+    changes: Vec<BrainChange>,
+}
+
+impl BrainDiff {
+    #[must_use]
+    pub fn changes(&self) -> &[BrainChange] {
+        &self.changes
+    }
+
+    #[must_use]
+    pub fn touches_neuron(&self, neuron: usize) -> bool {
+        self.changes
+            .iter()
+            .any(|change| change.neuron() == Some(neuron))
+    }
+
+    #[must_use]
+    pub fn touches_synapse(&self, from: usize, to: usize) -> bool {
+        self.changes
+            .iter()
+            .any(|change| change.synapse() == Some((from, to)))
+    }
+}
+
+impl Brain {
+    /// Compares neurons by index and synapses by innovation, as neither is ever removed.
+    #[must_use]
+    pub fn diff(&self, other: &Self) -> BrainDiff {
+        let mut changes = vec![];
+        for (index, neuron) in other.neurons().iter().enumerate() {
+            let Some(original) = self.neurons().get(index) else {
+                changes.push(BrainChange::AddedNeuron { neuron: index });
+                continue;
+            };
+            let delta = neuron.bias().as_float() - original.bias().as_float();
+            if delta != 0.0 {
+                changes.push(BrainChange::ChangedBias {
+                    neuron: index,
+                    delta,
+                });
+            }
+            if neuron.activation() != original.activation() {
+                changes.push(BrainChange::ChangedActivation {
+                    neuron: index,
+                    activation: *neuron.activation(),
+                });
+            }
+        }
+
+        for synapse in other.synapses() {
+            let (from, to) = (synapse.from(), synapse.to());
+            let original = self
+                .synapses()
+                .iter()
+                .find(|original| original.innovation() == synapse.innovation());
+            let was_active = matches!(original, Some(original) if original.active());
+            match (was_active, synapse.active()) {
+                (false, true) => changes.push(BrainChange::AddedSynapse { from, to }),
+                (true, false) => changes.push(BrainChange::DeactivatedSynapse { from, to }),
+                _ => {}
+            }
+            if let Some(original) = original {
+                let delta = synapse.weight().as_float() - original.weight().as_float();
+                if delta != 0.0 {
+                    changes.push(BrainChange::ChangedWeight { from, to, delta });
+                }
+            }
+        }
+        BrainDiff { changes }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use genesis_newtype::{Bias, Weight};
+
+    use super::*;
+
+    #[test]
+    fn diff_of_identical_brains_is_empty() {
+        let mut brain = Brain::new(2, 1);
+        brain.add_synapse(0, 2, Weight::new(0.5).unwrap()).unwrap();
+        assert!(brain.diff(&brain.clone()).changes().is_empty());
+    }
+
+    #[test]
+    fn diff_records_each_kind_of_change() {
+        let mut parent = Brain::new(2, 1);
+        parent.add_synapse(0, 2, Weight::new(0.5).unwrap()).unwrap();
+        parent.add_synapse(1, 2, Weight::new(0.5).unwrap()).unwrap();
+
+        let mut child = parent.clone();
+        child.synapses[1].set_weight(Weight::new(0.75).unwrap());
+        let hidden = child.add_neuron(0).unwrap();
+        child.neurons[2].set_bias(Bias::new(0.25).unwrap());
+
+        let diff = parent.diff(&child);
+        assert_eq!(
+            diff.changes(),
+            &[
+                BrainChange::ChangedBias {
+                    neuron: 2,
+                    delta: 0.25
+                },
+                BrainChange::AddedNeuron { neuron: hidden },
+                BrainChange::DeactivatedSynapse { from: 0, to: 2 },
+                BrainChange::ChangedWeight {
+                    from: 1,
+                    to: 2,
+                    delta: 0.25
+                },
+                BrainChange::AddedSynapse {
+                    from: 0,
+                    to: hidden
+                },
+                BrainChange::AddedSynapse {
+                    from: hidden,
+                    to: 2
+                },
+            ]
+        );
+        assert!(diff.touches_neuron(hidden));
+        assert!(diff.touches_synapse(1, 2));
+        assert!(!diff.touches_neuron(0));
+    }
+}
@@ -0,0 +1,172 @@
+use std::fmt::Write;
+
+use genesis_color as color;
+use genesis_config as config;
+use serde::Serialize;
+
+use crate::{feed_forward_layers, Brain, NeuronKind};
+
+/// The same negative to positive palette the brain panel uses for synapses.
+const WEIGHT_COLORS: &[(u8, u8, u8)] = &[(255, 0, 0), (160, 160, 160), (0, 150, 0)];
+
+/// A neuron in the node-link export.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct GraphNode {
+    pub id: usize,
+    pub label: String,
+    pub kind: NeuronKind,
+    pub activation: String,
+    pub bias: f32,
+}
+
+/// An active synapse in the node-link export.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct GraphLink {
+    pub source: usize,
+    pub target: usize,
+    pub weight: f32,
+}
+
+/// A node-link view of a brain, in the shape graph tools such as d3 and networkx read.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct BrainGraph {
+    pub directed: bool,
+    pub nodes: Vec<GraphNode>,
+    pub links: Vec<GraphLink>,
+}
+
+impl Brain {
+    /// Sensor and actuator names are used when the brain has the simulation's inputs and outputs.
+    #[must_use]
+    pub fn neuron_label(&self, index: usize) -> String {
+        let named = self.inputs == config::INPUT_NEURONS && self.outputs == config::OUTPUT_NEURONS;
+        match self.neurons.get(index).map(|neuron| *neuron.kind()) {
+            Some(NeuronKind::Input | NeuronKind::Output) if named => {
+                config::NEURON_NAMES[index].to_string()
+            }
+            Some(NeuronKind::Input) => format!("Input {index}"),
+            Some(NeuronKind::Output) => format!("Output {}", index - self.inputs),
+            _ => format!("Hidden {index}"),
+        }
+    }
+
+    /// Renders the brain as a Graphviz digraph, ranked by feed forward layer, with edges
+    /// coloured and sized against `max_weight`.
+    #[must_use]
+    pub fn to_dot(&self, max_weight: f32) -> String {
+        let mut dot = String::from("digraph brain {\n    rankdir=LR;\n    node [shape=ellipse];\n");
+        for (index, neuron) in self.neurons.iter().enumerate() {
+            let _ = writeln!(
+                dot,
+                "    n{index} [label=\"{}\\n{}\"];",
+                self.neuron_label(index),
+                neuron.activation().display()
+            );
+        }
+
+        let is_hidden = |index: &usize| matches!(self.neurons[*index].kind(), NeuronKind::Hidden);
+        let mut ranks = vec![(0..self.inputs).collect::<Vec<_>>()];
+        for layer in feed_forward_layers(self.neurons.clone(), self.synapses.clone()) {
+            let mut hidden: Vec<usize> = layer.into_iter().filter(is_hidden).collect();
+            hidden.sort_unstable();
+            ranks.push(hidden);
+        }
+        ranks.push((self.inputs..self.inputs + self.outputs).collect());
+        for rank in ranks.iter().filter(|rank| !rank.is_empty()) {
+            let nodes: Vec<String> = rank.iter().map(|index| format!("n{index};")).collect();
+            let _ = writeln!(dot, "    {{ rank=same; {} }}", nodes.join(" "));
+        }
+
+        for synapse in self.synapses.iter().filter(|synapse| synapse.active()) {
+            let (r, g, b) = color::interpolate_color(synapse.weight(), max_weight, WEIGHT_COLORS);
+            let weight = synapse.weight().as_float();
+            let _ = writeln!(
+                dot,
+                "    n{} -> n{} [color=\"#{r:02x}{g:02x}{b:02x}\", penwidth={:.2}, tooltip=\"{weight:.2}\"];",
+                synapse.from(),
+                synapse.to(),
+                (weight.abs() / max_weight).mul_add(4.0, 0.5)
+            );
+        }
+        dot.push_str("}\n");
+        dot
+    }
+
+    /// Every neuron and active synapse as a node-link graph, ready to serialise to JSON.
+    #[must_use]
+    pub fn to_graph(&self) -> BrainGraph {
+        let nodes = self
+            .neurons
+            .iter()
+            .enumerate()
+            .map(|(index, neuron)| GraphNode {
+                id: index,
+                label: self.neuron_label(index),
+                kind: *neuron.kind(),
+                activation: neuron.activation().display().to_string(),
+                bias: neuron.bias().as_float(),
+            })
+            .collect();
+        let links = self
+            .synapses
+            .iter()
+            .filter(|synapse| synapse.active())
+            .map(|synapse| GraphLink {
+                source: synapse.from(),
+                target: synapse.to(),
+                weight: synapse.weight().as_float(),
+            })
+            .collect();
+        BrainGraph {
+            directed: true,
+            nodes,
+            links,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use genesis_newtype::Weight;
+
+    use super::*;
+
+    #[test]
+    fn dot_ranks_layers_and_colours_edges() {
+        let mut brain = Brain::new(2, 1);
+        brain.add_synapse(0, 2, Weight::new(1.0).unwrap()).unwrap();
+        brain.add_synapse(1, 2, Weight::new(-1.0).unwrap()).unwrap();
+        let hidden = brain.add_neuron(0).unwrap();
+
+        let dot = brain.to_dot(1.0);
+        assert!(dot.starts_with("digraph brain {"));
+        assert!(dot.contains("n0 [label=\"Input 0\\nIdentity\"];"));
+        assert!(dot.contains("n2 [label=\"Output 0\\nTanh\"];"));
+        assert!(dot.contains(&format!("n{hidden} [label=\"Hidden {hidden}\\n")));
+        assert!(dot.contains("{ rank=same; n0; n1; }"));
+        assert!(dot.contains(&format!("{{ rank=same; n{hidden}; }}")));
+        assert!(dot.contains("{ rank=same; n2; }"));
+        assert!(dot.contains("n1 -> n2 [color=\"#ff0000\", penwidth=4.50"));
+        assert!(!dot.contains("n0 -> n2 "));
+    }
+
+    #[test]
+    fn graph_uses_sensor_names_and_skips_inactive_synapses() {
+        let mut brain = Brain::new(config::INPUT_NEURONS, config::OUTPUT_NEURONS);
+        let output = config::INPUT_NEURONS + config::ATTACK_INDEX;
+        brain
+            .add_synapse(config::ENERGY_INDEX, output, Weight::new(0.5).unwrap())
+            .unwrap();
+        brain.add_neuron(0).unwrap();
+
+        let graph = brain.to_graph();
+        assert_eq!(graph.nodes.len(), brain.neurons().len());
+        assert_eq!(graph.nodes[config::ENERGY_INDEX].label, "Energy");
+        assert_eq!(graph.nodes[output].label, "Attack");
+        assert_eq!(graph.links.len(), 2);
+        assert!(graph
+            .links
+            .iter()
+            .all(|link| link.source != config::ENERGY_INDEX || link.target != output));
+    }
+}
@@ -9,8 +9,11 @@
 #![allow(clippy::option_if_let_else)]
 mod activation;
 pub mod brain_error;
+mod diff;
+mod export;
 mod graph;
 pub mod neuron;
+mod prune;
 pub mod synapse;
 
 use std::collections::HashSet;
@@ -19,12 +22,14 @@ pub use activation::ActivationFunctionKind;
 use bevy_reflect::Reflect;
 pub use brain_error::BrainError;
 use derive_getters::Getters;
+pub use diff::{BrainChange, BrainDiff};
+pub use export::{BrainGraph, GraphLink, GraphNode};
 use genesis_config as config;
-use genesis_newtype::{Bias, Probability, Weight};
+use genesis_newtype::Weight;
 pub use graph::feed_forward_layers;
 pub use neuron::{Neuron, NeuronKind, Neurons, NeuronsExt};
 use rand::{prelude::*, seq::SliceRandom};
-use rand_distr::StandardNormal;
+use rand_distr::{Poisson, StandardNormal};
 use serde::{Deserialize, Serialize};
 use synapse::SynapsesExt;
 pub use synapse::{create_synapses, Synapse, Synapses};
@@ -38,6 +43,10 @@ pub struct BrainMutationThresholds {
     synapse_weight: f32,
     deactivate_synapse: f32,
     add_synapse: f32,
+    mean_mutations: f32,
+    max_weight: f32,
+    mutation_sigma: f32,
+    replace_probability: f32,
 }
 
 impl BrainMutationThresholds {
@@ -58,10 +67,30 @@ impl BrainMutationThresholds {
             synapse_weight,
             deactivate_synapse,
             add_synapse,
+            mean_mutations: *brain_config.mean_mutations(),
+            max_weight: *brain_config.max_weight(),
+            mutation_sigma: *brain_config.mutation_sigma(),
+            replace_probability: *brain_config.replace_probability(),
         }
     }
 }
 
+/// Draws how many mutations an egg gets from a Poisson distribution.
+fn mutation_count(rng: &mut dyn RngCore, mean: f32) -> usize {
+    Poisson::new(mean).map_or(0, |poisson| poisson.sample(rng) as usize)
+}
+
+/// Perturbs a weight or bias by a normal offset, or occasionally replaces it outright,
+/// keeping it within plus or minus `max_weight`.
+fn mutate_weight(weight: Weight, sigma: f32, replace_probability: f32, max_weight: f32) -> Weight {
+    let mut rng = thread_rng();
+    if rng.gen_bool(f64::from(replace_probability.clamp(0.0, 1.0))) {
+        return Weight::random_within(max_weight);
+    }
+    let offset: f32 = rng.sample(StandardNormal);
+    Weight::clamped(offset.mul_add(sigma, weight.as_float()), max_weight)
+}
+
 #[derive(Deserialize)]
 struct DeserBrain {
     neurons: Vec<Neuron>,
@@ -126,6 +155,48 @@ impl Brain {
         self.outputs
     }
 
+    /// Appends input and output neurons so a brain saved with fewer of them matches the
+    /// current layout, moving every later neuron and the synapses touching it up to make room.
+    /// Returns whether any neurons were added.
+    pub fn extend_io(&mut self, inputs: usize, outputs: usize) -> Result<bool, BrainError> {
+        if inputs < self.inputs || outputs < self.outputs {
+            return Err(BrainError::IoNeuronRemovalError);
+        }
+        let (new_inputs, new_outputs) = (inputs - self.inputs, outputs - self.outputs);
+        if new_inputs == 0 && new_outputs == 0 {
+            return Ok(false);
+        }
+        let (old_inputs, old_outputs) = (self.inputs, self.outputs);
+        let remap = |index: usize| match index {
+            i if i < old_inputs => i,
+            i if i < old_inputs + old_outputs => i + new_inputs,
+            i => i + new_inputs + new_outputs,
+        };
+
+        let hidden = self.neurons.split_off(old_inputs + old_outputs);
+        let outputs_neurons = self.neurons.split_off(old_inputs);
+        self.neurons
+            .extend((0..new_inputs).map(|_| Neuron::new(NeuronKind::Input)));
+        self.neurons.extend(outputs_neurons);
+        self.neurons
+            .extend((0..new_outputs).map(|_| Neuron::new(NeuronKind::Output)));
+        self.neurons.extend(hidden);
+
+        self.synapses = self
+            .synapses
+            .iter()
+            .map(|syn| {
+                let mut moved =
+                    Synapse::with_weight(remap(syn.from()), remap(syn.to()), syn.weight())?;
+                moved.set_active(syn.active());
+                Ok(moved)
+            })
+            .collect::<Result<_, BrainError>>()?;
+        self.inputs = inputs;
+        self.outputs = outputs;
+        Ok(true)
+    }
+
     #[must_use]
     pub fn neurons(&self) -> &[Neuron] {
         self.neurons.as_ref()
@@ -137,6 +208,12 @@ impl Brain {
     }
 
     pub fn activate(&self, input_values: &[f32]) -> Result<Vec<f32>, BrainError> {
+        let values = self.activate_all(input_values)?;
+        Ok(values[self.inputs..(self.inputs + self.outputs)].to_vec())
+    }
+
+    /// Activates the brain, returning the value of every neuron rather than just the outputs.
+    pub fn activate_all(&self, input_values: &[f32]) -> Result<Vec<f32>, BrainError> {
         if input_values.len() != self.inputs {
             return Err(BrainError::InputArrayError);
         }
@@ -167,34 +244,55 @@ impl Brain {
             }
         }
 
-        Ok(stored_values[self.inputs..(self.inputs + self.outputs)].to_vec())
+        Ok(stored_values)
     }
 
+    /// Applies a Poisson distributed number of mutations, averaging `mean_mutations`.
     #[must_use]
     pub fn mutate(
         &self,
         rng: &mut dyn RngCore,
-        chance: &Probability,
+        mean_mutations: f32,
+        brain_config: &BrainMutationThresholds,
+    ) -> Self {
+        let count = mutation_count(rng, mean_mutations);
+        self.mutate_times(rng, count, brain_config)
+    }
+
+    #[must_use]
+    pub fn mutate_times(
+        &self,
+        rng: &mut dyn RngCore,
+        count: usize,
         brain_config: &BrainMutationThresholds,
     ) -> Self {
         let mut new_brain = self.clone();
-        if rng.gen_bool(f64::from(chance.as_float())) {
-            match rng.gen_range(0.0..=1.0) {
-                x if x < *brain_config.deactivate_neuron() => new_brain.deactivate_random_neuron(),
-                x if x < *brain_config.add_neuron() => new_brain.add_random_neuron(),
-                x if x < *brain_config.neuron_bias() => new_brain.mutate_neuron_bias(),
-                x if x < *brain_config.activation_func() => new_brain.mutate_neuron_activation(),
-                x if x < *brain_config.synapse_weight() => new_brain.mutate_synapse_weight(),
-                x if x < *brain_config.deactivate_synapse() => {
-                    new_brain.deactivate_random_synapse()
-                }
-                _ => new_brain.add_random_synapse(),
-            }
+        for _ in 0..count {
+            new_brain.mutate_once(rng, brain_config);
         }
-
         new_brain
     }
 
+    fn mutate_once(&mut self, rng: &mut dyn RngCore, brain_config: &BrainMutationThresholds) {
+        match rng.gen_range(0.0..=1.0) {
+            x if x < *brain_config.deactivate_neuron() => self.deactivate_random_neuron(),
+            x if x < *brain_config.add_neuron() => self.add_random_neuron(),
+            x if x < *brain_config.neuron_bias() => self.mutate_neuron_bias(
+                *brain_config.mutation_sigma(),
+                *brain_config.replace_probability(),
+                *brain_config.max_weight(),
+            ),
+            x if x < *brain_config.activation_func() => self.mutate_neuron_activation(),
+            x if x < *brain_config.synapse_weight() => self.mutate_synapse_weight(
+                *brain_config.mutation_sigma(),
+                *brain_config.replace_probability(),
+                *brain_config.max_weight(),
+            ),
+            x if x < *brain_config.deactivate_synapse() => self.deactivate_random_synapse(),
+            _ => self.add_random_synapse(*brain_config.max_weight()),
+        }
+    }
+
     #[must_use]
     pub fn innovations(&self) -> Vec<usize> {
         self.synapses
@@ -233,7 +331,7 @@ impl Brain {
         }
     }
 
-    pub fn add_random_synapse(&mut self) {
+    pub fn add_random_synapse(&mut self, max_weight: f32) {
         let existing_from_to = self.synapses.get_active_from_to();
 
         let mut possible_from_to: Vec<(usize, usize)> = (0..self.neurons.len())
@@ -262,7 +360,7 @@ impl Brain {
 
         let picked_from_to = possible_from_to.choose(&mut rand::thread_rng());
         if let Some(from_to) = picked_from_to {
-            self.add_synapse(from_to.0, from_to.1, Weight::random())
+            self.add_synapse(from_to.0, from_to.1, Weight::random_within(max_weight))
                 .unwrap();
         }
     }
@@ -324,17 +422,19 @@ impl Brain {
         }
     }
 
-    pub fn mutate_synapse_weight(&mut self) {
+    pub fn mutate_synapse_weight(&mut self, sigma: f32, replace_probability: f32, max_weight: f32) {
         let random_synapse = self.synapses.choose_mut(&mut rand::thread_rng());
         if let Some(syn) = random_synapse {
-            let offset: f32 = thread_rng().sample(StandardNormal);
-            let new_weight =
-                Weight::new((syn.weight().as_float() + offset).clamp(-1.0, 1.0)).unwrap();
-            syn.set_weight(new_weight);
+            syn.set_weight(mutate_weight(
+                syn.weight(),
+                sigma,
+                replace_probability,
+                max_weight,
+            ));
         }
     }
 
-    pub fn mutate_neuron_bias(&mut self) {
+    pub fn mutate_neuron_bias(&mut self, sigma: f32, replace_probability: f32, max_weight: f32) {
         let mut connected_neurons: Vec<&mut Neuron> = self
             .neurons
             .iter_mut()
@@ -352,9 +452,7 @@ impl Brain {
             return;
         };
 
-        let offset: f32 = thread_rng().sample(StandardNormal);
-        let new_bias =
-            Bias::new((random_neuron.bias().as_float() + offset).clamp(-1.0, 1.0)).unwrap();
+        let new_bias = mutate_weight(random_neuron.bias(), sigma, replace_probability, max_weight);
 
         random_neuron.set_bias(new_bias);
     }
@@ -568,6 +666,39 @@ mod tests {
 
     use crate::{activation::ActivationFunctionKind, graph::feed_forward_layers, SynapsesExt};
 
+    #[test]
+    fn extend_io_keeps_every_connection() {
+        let mut test_brain = super::Brain::new(2, 2);
+        let w = Weight::new(0.5).unwrap();
+        test_brain.add_synapse(0, 2, w).unwrap();
+        test_brain.add_synapse(1, 3, w).unwrap();
+        let hidden = test_brain.add_neuron(0).unwrap();
+        let old_output = test_brain.activate(&[1.0, 1.0]).unwrap();
+
+        assert!(test_brain.extend_io(3, 4).unwrap());
+
+        assert_eq!((test_brain.inputs(), test_brain.outputs()), (3, 4));
+        assert_eq!(test_brain.neurons().len(), 8);
+        assert_eq!(
+            test_brain.neurons()[hidden + 3].kind(),
+            &super::NeuronKind::Hidden
+        );
+        let links: Vec<(usize, usize, bool)> = test_brain
+            .synapses()
+            .iter()
+            .map(|syn| (syn.from(), syn.to(), syn.active()))
+            .collect();
+        assert_eq!(
+            links,
+            vec![(0, 3, false), (1, 4, true), (0, 7, true), (7, 3, true)]
+        );
+        let new_output = test_brain.activate(&[1.0, 1.0, 1.0]).unwrap();
+        assert_eq!(new_output[..2], old_output[..]);
+        assert_eq!(new_output[2..], [0.0, 0.0]);
+        assert!(!test_brain.extend_io(3, 4).unwrap());
+        assert!(test_brain.extend_io(2, 4).is_err());
+    }
+
     #[test]
     fn add_new_synapse_from_out_to_in() {
         let mut test_brain = super::Brain::new(3, 2);
@@ -780,6 +911,21 @@ mod tests {
         assert_ne!(result, vec![0.0]);
     }
 
+    #[test]
+    fn activate_all_includes_hidden_neurons() {
+        let mut test_brain = super::Brain::new(1, 1);
+        let w = Weight::new(1.0).unwrap();
+
+        test_brain.add_synapse(0, 1, w).unwrap();
+        test_brain.add_neuron(0).unwrap();
+
+        let values = test_brain.activate_all(&[10.0]).unwrap();
+
+        assert_eq!(values.len(), 3);
+        assert_eq!(values[0], 10.0);
+        assert_eq!(values[1..2], test_brain.activate(&[10.0]).unwrap());
+    }
+
     #[test]
     fn activate_with_hidden_neuron() {
         let mut test_brain = super::Brain::new(1, 1);
@@ -817,7 +963,7 @@ mod tests {
     fn add_random_synapse_basic() {
         let mut test_brain = super::Brain::new(3, 3);
 
-        test_brain.add_random_synapse();
+        test_brain.add_random_synapse(1.0);
 
         assert_eq!(1, test_brain.synapses().len());
     }
@@ -826,9 +972,9 @@ mod tests {
     fn add_random_synapse_hidden_present() {
         let mut test_brain = super::Brain::new(3, 3);
 
-        test_brain.add_random_synapse();
+        test_brain.add_random_synapse(1.0);
         test_brain.add_neuron(0).unwrap();
-        test_brain.add_random_synapse();
+        test_brain.add_random_synapse(1.0);
 
         assert_eq!(3, test_brain.synapses().get_active_indices().len());
     }
@@ -837,7 +983,7 @@ mod tests {
     fn deactivate_random_synapse_no_changes() {
         let mut test_brain = super::Brain::new(3, 3);
 
-        test_brain.add_random_synapse();
+        test_brain.add_random_synapse(1.0);
         test_brain.add_neuron(0).unwrap();
         test_brain.deactivate_random_synapse();
 
@@ -932,7 +1078,7 @@ mod tests {
     #[test]
     fn mutate_synapse_weight_no_synapse_does_not_panic() {
         let mut test_brain = super::Brain::new(1, 1);
-        test_brain.mutate_synapse_weight();
+        test_brain.mutate_synapse_weight(1.0, 0.0, 1.0);
     }
 
     #[test]
@@ -942,18 +1088,39 @@ mod tests {
 
         test_brain.add_synapse(0, 1, w).unwrap();
 
-        test_brain.mutate_synapse_weight();
+        test_brain.mutate_synapse_weight(1.0, 0.0, 1.0);
 
         assert_ne!(0.0, test_brain.synapses()[0].weight().as_float());
     }
 
+    #[test]
+    fn mutation_counts_average_the_mean() {
+        let mut rng = rand::thread_rng();
+        assert_eq!(super::mutation_count(&mut rng, 0.0), 0);
+        let total: usize = (0..2000)
+            .map(|_| super::mutation_count(&mut rng, 2.0))
+            .sum();
+        let mean = total as f32 / 2000.0;
+        assert!((1.8..2.2).contains(&mean));
+    }
+
+    #[test]
+    fn mutated_weights_stay_in_bounds() {
+        let weight = Weight::new(0.5).unwrap();
+        assert_eq!(super::mutate_weight(weight, 0.0, 0.0, 1.0), weight);
+        for _ in 0..100 {
+            let mutated = super::mutate_weight(weight, 5.0, 0.5, 3.0);
+            assert!(mutated.as_float().abs() <= 3.0);
+        }
+    }
+
     #[test]
     fn mutate_neuron_bias_success() {
         let mut test_brain = super::Brain::new(1, 1);
         test_brain.add_synapse(0, 1, Weight::random()).unwrap();
         let starting_bias_in = test_brain.neurons()[0].bias();
         let starting_bias_out = test_brain.neurons()[1].bias();
-        test_brain.mutate_neuron_bias();
+        test_brain.mutate_neuron_bias(1.0, 0.0, 1.0);
 
         assert!(
             ((starting_bias_in != test_brain.neurons()[0].bias())
@@ -0,0 +1,124 @@
+use std::collections::HashSet;
+
+use crate::{feed_forward_layers, Brain, NeuronKind, Synapse};
+
+fn is_live(synapse: &Synapse, live: &HashSet<usize>) -> bool {
+    live.contains(&synapse.from()) && live.contains(&synapse.to())
+}
+
+impl Brain {
+    /// Neurons whose value can change an output: computed outputs, and the inputs and computed
+    /// hidden neurons with a path to one.
+    ///
+    /// Only active synapses decide which neurons are computed, but once computed a neuron's value
+    /// is carried by every synapse leaving it, active or not.
+    fn live_neurons(&self) -> HashSet<usize> {
+        let mut computed: HashSet<usize> = (0..self.inputs).collect();
+        for layer in feed_forward_layers(self.neurons.clone(), self.synapses.clone()) {
+            computed.extend(layer);
+        }
+        let mut live: HashSet<usize> = computed
+            .iter()
+            .copied()
+            .filter(|index| matches!(self.neurons[*index].kind(), NeuronKind::Output))
+            .collect();
+        loop {
+            let feeding: HashSet<usize> = self
+                .synapses
+                .iter()
+                .filter(|synapse| {
+                    live.contains(&synapse.to())
+                        && computed.contains(&synapse.from())
+                        && !live.contains(&synapse.from())
+                })
+                .map(Synapse::from)
+                .collect();
+            if feeding.is_empty() {
+                break;
+            }
+            live.extend(feeding);
+        }
+        live
+    }
+
+    /// The number of synapses that can affect an output.
+    #[must_use]
+    pub fn live_synapse_count(&self) -> usize {
+        let live = self.live_neurons();
+        self.synapses
+            .iter()
+            .filter(|synapse| is_live(synapse, &live))
+            .count()
+    }
+
+    /// A copy with every synapse that cannot affect an output removed, such as those into or out
+    /// of dead-end hidden neurons.
+    ///
+    /// Inactive synapses are removed only when they are dead too: an inactive synapse between live
+    /// neurons still carries a value, so dropping it would change the outputs. Dead hidden neurons
+    /// are kept without connections, as neuron indices make up innovation numbers. The outputs are
+    /// unchanged for any input.
+    #[must_use]
+    pub fn pruned(&self) -> Self {
+        let live = self.live_neurons();
+        let synapses = self
+            .synapses
+            .iter()
+            .filter(|synapse| is_live(synapse, &live))
+            .copied()
+            .collect();
+        Self {
+            synapses,
+            ..self.clone()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use genesis_newtype::Weight;
+
+    use super::*;
+
+    #[test]
+    fn pruning_keeps_innovations_and_outputs() {
+        let w = Weight::new(0.5).unwrap();
+        let mut brain = Brain::new(2, 2);
+        brain.add_synapse(0, 2, w).unwrap();
+        brain.add_synapse(1, 3, w).unwrap();
+        // Splitting 0 -> 2 leaves it inactive, but it still carries input 0 to the output.
+        let hidden = brain.add_neuron(0).unwrap();
+        // A hidden neuron that never reaches an output.
+        let dead_end = brain.add_neuron(1).unwrap();
+        brain.add_synapse(1, 3, w).unwrap();
+        brain.synapses.retain(|synapse| synapse.from() != dead_end);
+
+        let pruned = brain.pruned();
+        assert_eq!(pruned.neurons().len(), brain.neurons().len());
+        assert!(pruned
+            .synapses()
+            .iter()
+            .any(|synapse| synapse.from() == 0 && synapse.to() == 2 && !synapse.active()));
+        assert!(pruned
+            .synapses()
+            .iter()
+            .all(|synapse| synapse.from() != dead_end && synapse.to() != dead_end));
+        assert!(pruned
+            .synapses()
+            .iter()
+            .any(|synapse| synapse.from() == hidden));
+        assert_eq!(pruned.live_synapse_count(), pruned.synapses().len());
+        assert_eq!(brain.live_synapse_count(), 4);
+        let innovations = brain.innovations();
+        assert!(pruned
+            .innovations()
+            .iter()
+            .all(|innovation| innovations.contains(innovation)));
+        for input in [[1.0, -1.0], [0.25, 0.75]] {
+            assert_eq!(
+                brain.activate(&input).unwrap(),
+                pruned.activate(&input).unwrap()
+            );
+        }
+    }
+}
@@ -2,9 +2,10 @@
 use genesis_newtype::Weight;
 use ndarray::Array;
 
+/// Picks a colour along `colors` for a weight between plus and minus `limit`.
 #[must_use]
-pub fn interpolate_color(weight: Weight, colors: &[(u8, u8, u8)]) -> (u8, u8, u8) {
-    let t = (weight.as_float() + 1.0) / 2.0;
+pub fn interpolate_color(weight: Weight, limit: f32, colors: &[(u8, u8, u8)]) -> (u8, u8, u8) {
+    let t = (weight.as_float() / limit + 1.0) / 2.0;
 
     let spline_coords = Array::linspace(0.0, 1.0, colors.len());
 
@@ -55,7 +56,7 @@ mod tests {
     fn test_weight_is_one() {
         let w = Weight::new(1.0).unwrap();
 
-        let color = interpolate_color(w, &[(0, 0, 0), (100, 100, 100), (200, 200, 200)]);
+        let color = interpolate_color(w, 1.0, &[(0, 0, 0), (100, 100, 100), (200, 200, 200)]);
 
         assert_eq!(color, (200, 200, 200));
     }
@@ -67,6 +67,14 @@ impl Vitality {
         )
     }
 
+    /// The most energy a body of `size` holds across its core, health and store.
+    #[must_use]
+    pub fn capacity(size: &Size) -> usize {
+        let size_uint = size.as_uint();
+        (config::CORE_MULTIPLIER + config::HEALTH_MULTIPLIER) * size_uint
+            + config::EnergyLimitConfig::global().energy_limit(size_uint)
+    }
+
     pub fn energy_store(&self) -> &ecosystem::EnergyReserve {
         &self.energy_store
     }
@@ -79,6 +87,10 @@ impl Vitality {
         &mut self.health.0
     }
 
+    pub const fn total_energy(&self) -> usize {
+        self.energy_store.0.amount() + self.health.0.amount() + self.core_reserve.0.amount()
+    }
+
     #[must_use]
     pub fn available_space(&self) -> usize {
         self.health().available_space() + self.energy_store().available_space()
@@ -143,6 +155,52 @@ impl Vitality {
     }
 }
 
+/// Venom damage still to be taken, dealt evenly over the poison duration.
+#[derive(Component, Debug, Default, Reflect)]
+#[reflect(Component)]
+pub struct Poisoned {
+    remaining: f32,
+    per_second: f32,
+    due: f32,
+    source_generation: usize,
+}
+
+impl Poisoned {
+    pub fn new(damage: f32, duration: f32, source_generation: usize) -> Self {
+        let mut poisoned = Self::default();
+        poisoned.add(damage, duration, source_generation);
+        poisoned
+    }
+
+    /// Adds more venom, restarting the duration for everything remaining.
+    pub fn add(&mut self, damage: f32, duration: f32, source_generation: usize) {
+        self.remaining += damage;
+        self.per_second = self.remaining / duration;
+        self.source_generation = source_generation;
+    }
+
+    /// The whole damage due after `seconds`, carrying any fraction to the next tick.
+    pub fn tick(&mut self, seconds: f32) -> usize {
+        self.due = self
+            .per_second
+            .mul_add(seconds, self.due)
+            .min(self.remaining);
+        let damage = self.due.floor();
+        self.due -= damage;
+        self.remaining -= damage;
+        damage as usize
+    }
+
+    pub fn is_spent(&self) -> bool {
+        self.remaining < 1.0
+    }
+
+    /// The generation of the bug that last poisoned this one.
+    pub const fn source_generation(&self) -> usize {
+        self.source_generation
+    }
+}
+
 pub struct BodyComponentPlugin;
 
 impl bevy_app::Plugin for BodyComponentPlugin {
@@ -152,6 +210,22 @@ impl bevy_app::Plugin for BodyComponentPlugin {
             .register_type::<Health>()
             .register_type::<HealthEfficiency>()
             .register_type::<CoreReserve>()
-            .register_type::<Vitality>();
+            .register_type::<Vitality>()
+            .register_type::<Poisoned>();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn poison_deals_all_damage_over_duration() {
+        let mut poisoned = Poisoned::new(10.0, 2.0, 3);
+        let dealt: usize = (0..40).map(|_| poisoned.tick(0.05)).sum();
+        assert_eq!(dealt, 10);
+        assert!(poisoned.is_spent());
+        assert_eq!(poisoned.tick(0.05), 0);
+        assert_eq!(poisoned.source_generation(), 3);
     }
 }
@@ -98,6 +98,22 @@ impl Stomach {
             / plant_energy_density
     }
 
+    pub const fn stored_energy(&self) -> usize {
+        self.plant_matter.energy().amount() + self.meat_matter.energy().amount()
+    }
+
+    #[must_use]
+    pub fn take_all_energy(&mut self) -> Energy {
+        let mut energy = self
+            .plant_matter
+            .take_energy(self.plant_matter.energy().amount());
+        energy.add_energy(
+            self.meat_matter
+                .take_energy(self.meat_matter.energy().amount()),
+        );
+        energy
+    }
+
     pub fn available_plant_space(&self) -> usize {
         self.plant_limit() - self.plant_matter.energy().amount()
     }
@@ -106,18 +122,24 @@ impl Stomach {
         self.meat_limit() - self.meat_matter.energy().amount()
     }
 
-    pub fn eat(&mut self, food: &mut Food, size: &Size) {
-        let toughness = food.toughness();
-        let food_chunk = ((**size / toughness) * config::EATING_MULTIPLIER).ceil();
-        if self.plant_matter.toughness() == toughness {
-            let requested_energy = self.available_plant_space().min(food_chunk as usize);
-            self.plant_matter
-                .add_energy(food.take_energy(requested_energy));
-        } else {
-            let requested_energy = self.available_meat_space().min(food_chunk as usize);
-            self.meat_matter
-                .add_energy(food.take_energy(requested_energy));
-        }
+    fn food_chunk(food: &Food, size: &Size) -> usize {
+        ((**size / food.toughness()) * config::EATING_MULTIPLIER).ceil() as usize
+    }
+
+    pub fn eat_plant(&mut self, food: &mut Food, size: &Size) {
+        let requested_energy = self
+            .available_plant_space()
+            .min(Self::food_chunk(food, size));
+        self.plant_matter
+            .add_energy(food.take_energy(requested_energy));
+    }
+
+    pub fn eat_meat(&mut self, food: &mut Food, size: &Size) {
+        let requested_energy = self
+            .available_meat_space()
+            .min(Self::food_chunk(food, size));
+        self.meat_matter
+            .add_energy(food.take_energy(requested_energy));
     }
 
     pub fn digestion_cost(&self) -> usize {
@@ -96,7 +96,7 @@ pub struct Adult;
 #[reflect(Component)]
 pub struct Generation(pub usize);
 
-#[derive(Debug, Component, Serialize, Deserialize, Clone, Reflect, Default)]
+#[derive(Debug, Component, Serialize, Deserialize, Clone, Reflect, Default, Getters)]
 #[reflect(Component)]
 pub struct Relations {
     entity: (u32, String),
@@ -132,6 +132,31 @@ impl Relations {
     }
 }
 
+/// Id of the founding ancestor, shared by every descendant.
+#[derive(
+    Debug,
+    Component,
+    Serialize,
+    Deserialize,
+    Deref,
+    Clone,
+    Copy,
+    Ord,
+    PartialOrd,
+    Eq,
+    PartialEq,
+    Reflect,
+    Default,
+)]
+#[reflect(Component)]
+pub struct Lineage(pub u32);
+
+impl Lineage {
+    pub fn founded_by(entity: Entity) -> Self {
+        Self(cantor_pairing(entity.generation(), entity.index()))
+    }
+}
+
 #[derive(Component, Debug, Deref, DerefMut, Reflect, Default, Constructor)]
 #[reflect(Component)]
 pub struct Size(f32);
@@ -161,8 +186,12 @@ impl SizeMultiplier {
 
     fn compute_multiplier(size: f32) -> Weight {
         let world_config = config::WorldConfig::global();
-        let min_size = world_config.dependent_attributes.hatch_size_bounds.0;
-        let max_size = world_config.attributes.max_size.1;
+        let min_size = world_config
+            .dependent_attributes
+            .trade_off("hatch_size")
+            .bounds
+            .0;
+        let max_size = world_config.attributes.max_size.max;
         let range = max_size - min_size;
         Weight::new(((max_size - size) / range).powf(1.4))
             .expect("Expected size multiplier to be a valid weight.")
@@ -177,14 +206,54 @@ impl SizeMultiplier {
 #[reflect(Component)]
 pub struct Plant;
 
+/// Index into `WorldConfig::plant_types` describing what kind of plant this is.
+#[derive(Component, Debug, Deref, Reflect, Default, Clone, Copy, PartialEq, Eq)]
+#[reflect(Component)]
+pub struct PlantType(pub usize);
+
+/// Marks a plant that has been partially eaten.
+#[derive(Component, Debug, Reflect, Default)]
+#[reflect(Component)]
+pub struct Grazed;
+
 pub fn plant_as_food(energy: Energy) -> Food {
-    let plant_config = &config::WorldConfig::global().plant;
+    plant_as_food_from_config(energy, &config::WorldConfig::global().plant)
+}
+
+pub fn plant_as_food_from_config(energy: Energy, plant_config: &config::PlantConfig) -> Food {
     Food::new(energy, plant_config.energy_density, plant_config.toughness)
 }
 #[derive(Component, Debug, Reflect, Default)]
 #[reflect(Component)]
 pub struct Meat;
 
+/// The organism a piece of meat came from.
+#[derive(Component, Debug, Clone, Copy, Reflect, Default, Getters)]
+#[reflect(Component)]
+pub struct MeatSource {
+    id: u32,
+    parent: Option<u32>,
+    lineage: Lineage,
+}
+
+impl MeatSource {
+    pub fn new(relations: &Relations, lineage: Lineage) -> Self {
+        Self {
+            id: relations.entity.0,
+            parent: relations.parent,
+            lineage,
+        }
+    }
+
+    /// Whether the meat came from a parent, child or sibling of `relations`.
+    pub fn is_kin_of(&self, relations: &Relations) -> bool {
+        relations.parent == Some(self.id)
+            || relations.children.contains(&self.id)
+            || self.parent == Some(relations.entity.0)
+            || (self.parent.is_some() && self.parent == relations.parent)
+    }
+}
+
 pub fn meat_as_food(energy: Energy) -> Food {
     let meat_config = &config::WorldConfig::global().meat;
     Food::new(energy, meat_config.energy_density, meat_config.toughness)
@@ -219,6 +288,9 @@ impl Plugin for ComponentsPlugin {
             .register_type::<genesis_brain::ActivationFunctionKind>()
             .register_type::<genesis_brain::Synapse>()
             .register_type::<Vec<genesis_brain::Synapse>>()
+            .register_type::<genesis_brain::BrainDiff>()
+            .register_type::<genesis_brain::BrainChange>()
+            .register_type::<Vec<genesis_brain::BrainChange>>()
             .register_type::<see::Vision>()
             .register_type::<BurntEnergy>()
             .register_type::<TranslationSum>()
@@ -229,10 +301,14 @@ impl Plugin for ComponentsPlugin {
             .register_type::<Adult>()
             .register_type::<Generation>()
             .register_type::<Relations>()
+            .register_type::<Lineage>()
             .register_type::<SizeMultiplier>()
             .register_type::<Size>()
             .register_type::<Plant>()
+            .register_type::<PlantType>()
+            .register_type::<Grazed>()
             .register_type::<Meat>()
+            .register_type::<MeatSource>()
             .register_component_as::<dyn BehaviourTracker, ThinkingSum>()
             .register_component_as::<dyn BehaviourTracker, TranslationSum>()
             .register_component_as::<dyn BehaviourTracker, RotationSum>()
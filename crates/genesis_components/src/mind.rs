@@ -8,8 +8,8 @@ use bevy_reflect::Reflect;
 use bevy_render::color::Color;
 use derive_more::{Deref, DerefMut, From};
 use genesis_brain::{
-    feed_forward_layers, ActivationFunctionKind, Brain, BrainMutationThresholds, NeuronKind,
-    Neurons, Synapses,
+    feed_forward_layers, ActivationFunctionKind, Brain, BrainDiff, BrainMutationThresholds,
+    NeuronKind, Neurons, Synapses,
 };
 use genesis_color as color;
 use genesis_config as config;
@@ -36,6 +36,12 @@ pub enum MindValidationError {
     InvalidSynapseTo(usize),
     #[error("Invalid 'from' index found on synapse '{0}'.")]
     InvalidSynapseFrom(usize),
+    #[error("Brain has more input or output neurons than this version supports.")]
+    TooManyNeurons,
+    #[error("Bias on neuron '{0}' is outside the configured max weight.")]
+    BiasOutOfBounds(usize),
+    #[error("Weight on synapse '{0}' is outside the configured max weight.")]
+    WeightOutOfBounds(usize),
 }
 
 #[derive(
@@ -71,7 +77,16 @@ impl Mind {
         mind_color(innovations)
     }
 
+    /// Pads a brain saved by an older version with the input and output neurons added since.
+    /// Returns whether it changed.
+    pub fn migrate(&mut self) -> Result<bool, MindValidationError> {
+        self.0
+            .extend_io(config::INPUT_NEURONS, config::OUTPUT_NEURONS)
+            .map_err(|_| MindValidationError::TooManyNeurons)
+    }
+
     pub fn validate(&self) -> Result<(), MindValidationError> {
+        let max_weight = *config::WorldConfig::global().brain_mutations.max_weight();
         let total_non_hidden = config::INPUT_NEURONS + config::OUTPUT_NEURONS;
         if self.neurons().len() < total_non_hidden {
             return Err(MindValidationError::NotEnoughNeurons);
@@ -100,6 +115,9 @@ impl Mind {
             {
                 return Err(MindValidationError::OutputNeuronStructure(i));
             }
+            if neuron.bias().as_float().abs() > max_weight {
+                return Err(MindValidationError::BiasOutOfBounds(i));
+            }
         }
         for (i, synapse) in self.synapses().iter().enumerate() {
             if !(config::INPUT_NEURONS..self.neurons().len()).contains(&synapse.to()) {
@@ -110,6 +128,9 @@ impl Mind {
             {
                 return Err(MindValidationError::InvalidSynapseFrom(i));
             }
+            if synapse.weight().as_float().abs() > max_weight {
+                return Err(MindValidationError::WeightOutOfBounds(i));
+            }
         }
 
         Ok(())
@@ -176,9 +197,16 @@ pub struct MindLayout {
 
 impl MindLayout {
     pub fn new(mind: &Mind) -> Self {
-        let neurons =
-            Self::layout_neurons(mind.neurons(), mind.synapses(), &START_POS, RADIUS, SPACING);
-        let synapses = Self::painted_synapses(mind.synapses(), &neurons);
+        let max_weight = *config::WorldConfig::global().brain_mutations.max_weight();
+        let neurons = Self::layout_neurons(
+            mind.neurons(),
+            mind.synapses(),
+            &START_POS,
+            RADIUS,
+            SPACING,
+            max_weight,
+        );
+        let synapses = Self::painted_synapses(mind.synapses(), &neurons, max_weight);
         Self { neurons, synapses }
     }
 
@@ -190,7 +218,11 @@ impl MindLayout {
         self.synapses.as_ref()
     }
 
-    fn painted_synapses(synapses: &Synapses, neuron_layout: &[GuiNeuron]) -> Vec<PaintedSynapse> {
+    fn painted_synapses(
+        synapses: &Synapses,
+        neuron_layout: &[GuiNeuron],
+        max_weight: f32,
+    ) -> Vec<PaintedSynapse> {
         let mut painted_synapses = vec![];
         let sorted_synapses = synapses
             .iter()
@@ -204,7 +236,7 @@ impl MindLayout {
         };
 
             let color = if syn.active() {
-                let (r, g, b) = color::interpolate_color(syn.weight(), COLOR_ARRAY);
+                let (r, g, b) = color::interpolate_color(syn.weight(), max_weight, COLOR_ARRAY);
                 egui::Color32::from_rgb(r, g, b)
             } else {
                 egui::Color32::BLACK
@@ -220,6 +252,7 @@ impl MindLayout {
         start: &(f32, f32),
         radius: f32,
         spacing: f32,
+        max_weight: f32,
     ) -> Vec<GuiNeuron> {
         let max_layer = 10;
         let impossible_layer = max_layer + 1;
@@ -259,7 +292,7 @@ impl MindLayout {
                     (layer_index as f32).mul_add(2.0f32.mul_add(radius, spacing), start.1),
                 ))
             };
-            let (r, g, b) = color::interpolate_color(neuron.bias(), COLOR_ARRAY);
+            let (r, g, b) = color::interpolate_color(neuron.bias(), max_weight, COLOR_ARRAY);
             let color = egui::Color32::from_rgb(r, g, b);
             positions.push(GuiNeuron {
                 index: k,
@@ -306,11 +339,33 @@ impl MindLayout {
     }
 }
 
+/// The synapses that can affect an output, counted once as every thought pays for each of them.
+#[derive(Component, Debug, Default, Clone, Copy, Deref)]
+pub struct LiveSynapses(usize);
+
+impl LiveSynapses {
+    pub fn new(mind: &Mind) -> Self {
+        Self(mind.live_synapse_count())
+    }
+}
+
+/// What changed in this mind when it was mutated from its parent's.
+#[derive(Component, Debug, Default, Clone, Deref, Reflect)]
+#[reflect(Component)]
+pub struct MindMutations(pub BrainDiff);
+
+impl MindMutations {
+    pub fn from_parent(parent: &Mind, child: &Mind) -> Self {
+        Self(parent.diff(child))
+    }
+}
+
 #[derive(Bundle, Debug)]
 pub struct MindBundle {
     pub input: MindInput,
     pub output: MindOutput,
     pub layout: MindLayout,
+    pub live_synapses: LiveSynapses,
 }
 
 impl MindBundle {
@@ -318,11 +373,13 @@ impl MindBundle {
         let input_vec = MindInput(vec![0.0; mind.inputs()]);
         let output_vec = MindOutput(vec![0.0; mind.outputs()]);
         let layout = MindLayout::new(mind);
+        let live_synapses = LiveSynapses::new(mind);
 
         Self {
             input: input_vec,
             output: output_vec,
             layout,
+            live_synapses,
         }
     }
 }
@@ -342,7 +399,8 @@ impl bevy_app::Plugin for MindComponentPlugin {
     fn build(&self, app: &mut bevy_app::App) {
         app.register_type::<Mind>()
             .register_type::<MindInput>()
-            .register_type::<MindOutput>();
+            .register_type::<MindOutput>()
+            .register_type::<MindMutations>();
     }
 }
 
@@ -369,6 +427,7 @@ mod tests {
             &(0.0, 0.0),
             10.0,
             10.0,
+            1.0,
         );
 
         assert_eq!(layout.iter().filter(|x| x.pos.is_some()).count(), 4);
@@ -9,9 +9,14 @@ pub struct Vision {
     pub bug_angle_score: f32,
     pub bug_dist_score: f32,
     pub bug_species: f32,
+    /// Visible eggs and bugs that this bug is the parent of.
+    visible_offspring: u32,
     visible_plant: u32,
     plant_angle_score: f32,
     plant_dist_score: f32,
+    /// Type id plus one of the closest visible plant over the number of plant types, so it lies
+    /// in 0 to 1 and is zero when no plant is visible.
+    plant_type: f32,
     visible_meat: u32,
     meat_angle_score: f32,
     meat_dist_score: f32,
@@ -24,9 +29,11 @@ impl Vision {
             bug_angle_score: 0.0,
             bug_dist_score: 1.0,
             bug_species: 0.0,
+            visible_offspring: 0,
             visible_plant: 0,
             plant_angle_score: 0.0,
             plant_dist_score: 1.0,
+            plant_type: 0.0,
             visible_meat: 0,
             meat_angle_score: 0.0,
             meat_dist_score: 1.0,
@@ -41,6 +48,10 @@ impl Vision {
         self.visible_bugs += 1;
     }
 
+    pub fn increment_offspring(&mut self) {
+        self.visible_offspring += 1;
+    }
+
     pub fn increment_plant(&mut self) {
         self.visible_plant += 1;
     }
@@ -60,10 +71,16 @@ impl Vision {
         self.visible_plant = visible_plant;
     }
 
-    pub fn set_plant_score(&mut self, plant_score: (f32, f32)) {
+    pub fn set_plant_score(
+        &mut self,
+        plant_score: (f32, f32),
+        plant_type: usize,
+        plant_type_count: usize,
+    ) {
         if self.plant_dist_score > plant_score.0 {
             self.plant_dist_score = plant_score.0;
             self.plant_angle_score = plant_score.1;
+            self.plant_type = (plant_type + 1) as f32 / plant_type_count.max(1) as f32;
         }
     }
 
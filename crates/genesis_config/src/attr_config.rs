@@ -1,181 +1,449 @@
+use std::collections::BTreeMap;
+
 use serde_derive::{Deserialize, Serialize};
 
-use super::validators::attribute_limit;
+use super::validators::{attribute_limit, between, low_high_tuple};
 
 type MinMax = (Option<f32>, Option<f32>);
 
-struct AttributeConfigValidator {
-    hatch_age: MinMax,
-    eye_range: MinMax,
-    cost_of_eating: MinMax,
-    offspring_energy: MinMax,
-    max_size: MinMax,
-    growth_rate: MinMax,
-    grab_angle: MinMax,
-    food_preference: MinMax,
-    base_attack: MinMax,
+/// Every gene: its attribute component, how the component stores the value, the default
+/// chromosome and the limits the chromosome is validated against.
+///
+/// Calls `$callback!` with the table, so each crate generates the code it needs from one list.
+/// The heritable mutation rate is optional and handled separately.
+#[macro_export]
+macro_rules! genes {
+    ($callback:ident) => {
+        $callback! {
+            hatch_age: HatchAge(value) = [10.0, 30.0, 15] in [9.0, 60.0],
+            eye_range: EyeRange(value) = [200.0, 700.0, 100] in [50.0, 2000.0],
+            cost_of_eating: CostOfEating(value) = [0.2, 0.3, 10] in [0.0, 1.0],
+            offspring_energy: OffspringEnergy(value) = [0.5, 1.0, 100] in [0.1, 1.0],
+            max_size: MaxSize(value) = [80.0, 100.0, 20] in [50.0, 150.0],
+            growth_rate: GrowthRate(value) = [0.05, 0.1, 20] in [0.0, 1.0],
+            grab_angle: GrabAngle(degrees) = [30.0, 60.0, 10] in [20.0, 90.0],
+            food_preference: FoodPreference(value) = [0.0, 1.0, 100] in [0.0, 1.0],
+            base_attack: BaseAttack(value) = [20.0, 200.0, 40] in [20.0, 200.0],
+            aspect_ratio: AspectRatio(value) = [0.8, 1.25, 10] in [0.5, 2.0],
+            body_density: BodyDensity(value) = [0.5, 1.5, 11] in [0.1, 10.0],
+            // Multiples of `max_translation` and `max_rotation`.
+            top_speed: TopSpeed(value) = [0.8, 1.2, 9] in [0.1, 3.0],
+            turn_rate: TurnRate(value) = [0.8, 1.2, 9] in [0.1, 3.0],
+            // Half width of the cone in front of a bug that its attacks reach.
+            attack_cone: AttackCone(degrees) = [8.0, 16.0, 9] in [2.0, 90.0],
+            // Fractions of damage dealt as poison, reflected to attackers and absorbed.
+            venom: Venom(value) = [0.0, 0.2, 11] in [0.0, 1.0],
+            spines: Spines(value) = [0.0, 0.2, 11] in [0.0, 1.0],
+            armour: Armour(value) = [0.0, 0.2, 11] in [0.0, 0.9],
+        }
+    };
 }
 
-impl Default for AttributeConfigValidator {
-    fn default() -> Self {
-        Self {
-            hatch_age: (Some(9.0), Some(60.0)),
-            eye_range: (Some(50.0), Some(2000.0)),
-            cost_of_eating: (Some(0.0), Some(1.0)),
-            offspring_energy: (Some(0.1), Some(1.0)),
-            max_size: (Some(50.0), Some(150.0)),
-            growth_rate: (Some(0.0), Some(1.0)),
-            grab_angle: (Some(20.0), Some(90.0)),
-            food_preference: (Some(0.0), Some(1.0)),
-            base_attack: (Some(20.0), Some(200.0)),
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum DeserChromosomeConfig {
+    MinMaxLen(f32, f32, usize),
+    Full {
+        min: f32,
+        max: f32,
+        steps: usize,
+        #[serde(default)]
+        mutation_rate: Option<f32>,
+        #[serde(default = "ChromosomeConfig::default_step_size")]
+        step_size: usize,
+        #[serde(default)]
+        kind: ChromosomeKind,
+        #[serde(default = "ChromosomeConfig::default_sigma")]
+        sigma: f32,
+    },
+}
+
+impl From<DeserChromosomeConfig> for ChromosomeConfig {
+    fn from(tmp: DeserChromosomeConfig) -> Self {
+        match tmp {
+            DeserChromosomeConfig::MinMaxLen(min, max, steps) => Self::new(min, max, steps),
+            DeserChromosomeConfig::Full {
+                min,
+                max,
+                steps,
+                mutation_rate,
+                step_size,
+                kind,
+                sigma,
+            } => Self {
+                min,
+                max,
+                steps,
+                mutation_rate,
+                step_size,
+                kind,
+                sigma,
+            },
         }
     }
 }
 
-type MinMaxLen = (f32, f32, usize);
+/// Whether a chromosome holds one of `steps` evenly spaced values or any value between `min`
+/// and `max`.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum ChromosomeKind {
+    #[default]
+    Discrete,
+    Continuous,
+}
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
-pub struct AttributeConfig {
-    pub hatch_age: MinMaxLen,
-    pub eye_range: MinMaxLen,
-    pub cost_of_eating: MinMaxLen,
-    pub offspring_energy: MinMaxLen,
-    pub max_size: MinMaxLen,
-    pub growth_rate: MinMaxLen,
-    pub grab_angle: MinMaxLen,
-    pub food_preference: MinMaxLen,
-    pub base_attack: MinMaxLen,
+/// The values a chromosome can take and how it mutates.
+///
+/// Written either as a `[min, max, steps]` list or as a table with the kind and mutation settings.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+#[serde(from = "DeserChromosomeConfig")]
+pub struct ChromosomeConfig {
+    pub min: f32,
+    pub max: f32,
+    pub steps: usize,
+    /// Chance of mutating when an egg is laid, `mutation_probability` if not set.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub mutation_rate: Option<f32>,
+    /// The most steps a single mutation can move the value.
+    pub step_size: usize,
+    pub kind: ChromosomeKind,
+    /// Standard deviation of a continuous mutation, as a fraction of the range.
+    pub sigma: f32,
 }
 
-impl Default for AttributeConfig {
-    fn default() -> Self {
+impl ChromosomeConfig {
+    pub const fn new(min: f32, max: f32, steps: usize) -> Self {
         Self {
-            hatch_age: (10.0, 30.0, 15),
-            eye_range: (200.0, 700.0, 100),
-            cost_of_eating: (0.2, 0.3, 10),
-            offspring_energy: (0.5, 1.0, 100),
-            max_size: (80.0, 100.0, 20),
-            growth_rate: (0.05, 0.1, 20),
-            grab_angle: (30.0, 60.0, 10),
-            food_preference: (0.0, 1.0, 100),
-            base_attack: (20.0, 200.0, 40),
+            min,
+            max,
+            steps,
+            mutation_rate: None,
+            step_size: Self::default_step_size(),
+            kind: ChromosomeKind::Discrete,
+            sigma: Self::default_sigma(),
+        }
+    }
+
+    const fn default_step_size() -> usize {
+        1
+    }
+
+    const fn default_sigma() -> f32 {
+        0.05
+    }
+
+    fn validate(&self, validator: MinMax, name: &str) -> Vec<Option<String>> {
+        let mut messages = attribute_limit(self.min, self.max, validator, name);
+        if let Some(rate) = self.mutation_rate {
+            messages.push(between(rate, 0.0, 1.0, &format!("{name} mutation_rate")));
         }
+        match self.kind {
+            ChromosomeKind::Discrete => messages.push(between(
+                self.step_size,
+                1,
+                self.steps.max(1),
+                &format!("{name} step_size"),
+            )),
+            ChromosomeKind::Continuous => {
+                messages.push(between(self.sigma, 0.0, 1.0, &format!("{name} sigma")));
+            }
+        }
+        messages
     }
 }
 
-impl AttributeConfig {
-    #[must_use]
-    pub(super) fn validate(&self) -> Vec<Option<String>> {
-        let validator = AttributeConfigValidator::default();
-        let mut messages = vec![];
+macro_rules! attribute_config {
+    ($($gene:ident: $component:ident($unit:ident) = [$min:expr, $max:expr, $steps:expr] in [$floor:expr, $ceil:expr]),+ $(,)?) => {
+        /// The genes a trade-off can take its source value from.
+        pub const GENES: &[&str] = &[$(stringify!($gene),)+ "mutation_rate"];
+
+        /// Genes missing from the config take their default chromosome.
+        #[derive(Debug, Serialize, Deserialize, Clone)]
+        #[serde(default)]
+        pub struct AttributeConfig {
+            $(pub $gene: ChromosomeConfig,)+
+            /// A heritable rate that replaces `mutation_probability`, and scales `mean_mutations` by
+            /// the same factor, when set.
+            #[serde(default)]
+            pub mutation_rate: Option<ChromosomeConfig>,
+        }
 
-        macro_rules! attrs_limit {
-            ($attr:ident) => {
-                messages.extend(attribute_limit(
-                    self.$attr.0,
-                    self.$attr.1,
-                    validator.$attr,
-                    stringify!($attr),
-                ))
-            };
-            ($attr:ident, $($attrs:ident), +) => {
-                attrs_limit!($attr);
-                attrs_limit!($($attrs), +)
+        impl Default for AttributeConfig {
+            fn default() -> Self {
+                Self {
+                    $($gene: ChromosomeConfig::new($min, $max, $steps),)+
+                    mutation_rate: None,
+                }
             }
         }
-        attrs_limit!(
-            hatch_age,
-            eye_range,
-            cost_of_eating,
-            offspring_energy,
-            max_size,
-            growth_rate,
-            grab_angle,
-            food_preference,
-            base_attack
-        );
+
+        impl AttributeConfig {
+            #[must_use]
+            pub(super) fn validate(&self) -> Vec<Option<String>> {
+                let mut messages = vec![];
+                $(messages.extend(
+                    self.$gene
+                        .validate((Some($floor), Some($ceil)), stringify!($gene)),
+                );)+
+                if let Some(mutation_rate) = &self.mutation_rate {
+                    messages.extend(
+                        mutation_rate.validate((Some(0.0), Some(1.0)), "mutation_rate"),
+                    );
+                }
+                messages
+            }
+        }
+    };
+}
+
+genes!(attribute_config);
+
+/// How a trade-off maps its source gene, normalised to 0..1, onto its bounds.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum TradeOffCurve {
+    /// From the lower bound up to the upper bound.
+    Linear,
+    /// From the upper bound down to the lower bound.
+    Inverse,
+    /// Geometrically from the lower bound up to the upper bound, both of which must be positive.
+    Exponential,
+}
+
+/// A dependent attribute derived from a gene, so a gain in the gene costs or grants something
+/// else.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct TradeOffConfig {
+    pub source: String,
+    pub curve: TradeOffCurve,
+    pub bounds: (f32, f32),
+}
+
+impl TradeOffConfig {
+    fn validate(&self, name: &str) -> Vec<Option<String>> {
+        let mut messages = vec![low_high_tuple(self.bounds, name)];
+        if !GENES.contains(&self.source.as_str()) {
+            messages.push(Some(format!(
+                "Trade-off '{name}' has an unknown source gene '{}'",
+                self.source
+            )));
+        }
+        if self.curve == TradeOffCurve::Exponential && self.bounds.0 <= 0.0 {
+            messages.push(Some(format!(
+                "Exponential trade-off '{name}' must have positive bounds"
+            )));
+        }
         messages
     }
 }
 
-struct DependentAttributeConfigValidator {
-    adult_age_bounds: MinMax,
-    death_age_bounds: MinMax,
-    eye_angle_bounds: MinMax,
-    mouth_width_bounds: MinMax,
-    hatch_size_bounds: MinMax,
-    grab_strength_bounds: MinMax,
-    base_defence_bounds: MinMax,
+/// The trade-offs that systems query through their own components: name, default source gene,
+/// curve and bounds, and the limits the bounds are validated against.
+const DEPENDENT_ATTRIBUTES: [(&str, &str, TradeOffCurve, (f32, f32), MinMax); 7] = [
+    (
+        "adult_age",
+        "hatch_age",
+        TradeOffCurve::Inverse,
+        (30.0, 60.0),
+        (Some(20.0), Some(100.0)),
+    ),
+    (
+        "death_age",
+        "max_size",
+        TradeOffCurve::Linear,
+        (400.0, 600.0),
+        (Some(350.0), Some(1000.0)),
+    ),
+    (
+        "eye_angle",
+        "eye_range",
+        TradeOffCurve::Inverse,
+        (30.0, 160.0),
+        (Some(20.0), Some(180.0)),
+    ),
+    (
+        "mouth_width",
+        "cost_of_eating",
+        TradeOffCurve::Linear,
+        (30.0, 90.0),
+        (Some(20.0), Some(180.0)),
+    ),
+    (
+        "hatch_size",
+        "hatch_age",
+        TradeOffCurve::Linear,
+        (20.0, 35.0),
+        (Some(10.0), Some(49.0)),
+    ),
+    (
+        "grab_strength",
+        "grab_angle",
+        TradeOffCurve::Inverse,
+        (0.01, 0.05),
+        (Some(0.0), Some(1.0)),
+    ),
+    (
+        "base_defence",
+        "base_attack",
+        TradeOffCurve::Inverse,
+        (0.3, 0.7),
+        (Some(0.2), Some(0.8)),
+    ),
+];
+
+/// Dependent attributes as they were written before they became trade-offs, when only their
+/// bounds could be set.
+#[derive(Deserialize)]
+struct DeserDependentAttributeConfig {
+    adult_age_bounds: Option<(f32, f32)>,
+    death_age_bounds: Option<(f32, f32)>,
+    eye_angle_bounds: Option<(f32, f32)>,
+    mouth_width_bounds: Option<(f32, f32)>,
+    hatch_size_bounds: Option<(f32, f32)>,
+    grab_strength_bounds: Option<(f32, f32)>,
+    base_defence_bounds: Option<(f32, f32)>,
+    #[serde(default)]
+    trade_offs: BTreeMap<String, TradeOffConfig>,
 }
 
-impl Default for DependentAttributeConfigValidator {
-    fn default() -> Self {
-        Self {
-            adult_age_bounds: (Some(20.0), Some(100.0)),
-            death_age_bounds: (Some(350.0), Some(1000.0)),
-            eye_angle_bounds: (Some(20.0), Some(180.0)),
-            mouth_width_bounds: (Some(20.0), Some(180.0)),
-            hatch_size_bounds: (Some(10.0), Some(49.0)),
-            grab_strength_bounds: (Some(0.0), Some(1.0)),
-            base_defence_bounds: (Some(0.2), Some(0.8)),
+impl From<DeserDependentAttributeConfig> for DependentAttributeConfig {
+    fn from(tmp: DeserDependentAttributeConfig) -> Self {
+        let mut config = Self::default();
+        let old_bounds = [
+            ("adult_age", tmp.adult_age_bounds),
+            ("death_age", tmp.death_age_bounds),
+            ("eye_angle", tmp.eye_angle_bounds),
+            ("mouth_width", tmp.mouth_width_bounds),
+            ("hatch_size", tmp.hatch_size_bounds),
+            ("grab_strength", tmp.grab_strength_bounds),
+            ("base_defence", tmp.base_defence_bounds),
+        ];
+        for (name, bounds) in old_bounds {
+            if let (Some(bounds), Some(trade_off)) = (bounds, config.trade_offs.get_mut(name)) {
+                trade_off.bounds = bounds;
+            }
         }
+        config.trade_offs.extend(tmp.trade_offs);
+        config
     }
 }
 
+/// Trade-offs by name, shown and recorded alongside the attributes.
+///
+/// Always holds the dependent attributes that systems query, which take their default source,
+/// curve and bounds unless configured. The old `<name>_bounds` keys still set their bounds.
 #[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(from = "DeserDependentAttributeConfig")]
 pub struct DependentAttributeConfig {
-    pub adult_age_bounds: (f32, f32),
-    pub death_age_bounds: (f32, f32),
-    pub eye_angle_bounds: (f32, f32),
-    pub mouth_width_bounds: (f32, f32),
-    pub hatch_size_bounds: (f32, f32),
-    pub grab_strength_bounds: (f32, f32),
-    pub base_defence_bounds: (f32, f32),
+    pub trade_offs: BTreeMap<String, TradeOffConfig>,
 }
 
 impl Default for DependentAttributeConfig {
     fn default() -> Self {
-        Self {
-            adult_age_bounds: (30.0, 60.0),
-            death_age_bounds: (400.0, 600.0),
-            eye_angle_bounds: (30.0, 160.0),
-            mouth_width_bounds: (30.0, 90.0),
-            hatch_size_bounds: (20.0, 35.0),
-            grab_strength_bounds: (0.01, 0.05),
-            base_defence_bounds: (0.3, 0.7),
-        }
+        let trade_offs = DEPENDENT_ATTRIBUTES
+            .iter()
+            .map(|(name, source, curve, bounds, _)| {
+                let trade_off = TradeOffConfig {
+                    source: (*source).to_string(),
+                    curve: *curve,
+                    bounds: *bounds,
+                };
+                ((*name).to_string(), trade_off)
+            })
+            .collect();
+        Self { trade_offs }
     }
 }
 
 impl DependentAttributeConfig {
+    /// A dependent attribute that systems query, such as `hatch_size`.
+    ///
+    /// # Panics
+    ///
+    /// If `name` is not one of the dependent attributes.
+    #[must_use]
+    pub fn trade_off(&self, name: &str) -> &TradeOffConfig {
+        self.trade_offs
+            .get(name)
+            .unwrap_or_else(|| panic!("'{name}' is not a dependent attribute"))
+    }
+
+    /// The configured trade-offs that have no component of their own.
+    pub fn extra_trade_offs(&self) -> impl Iterator<Item = (&String, &TradeOffConfig)> {
+        self.trade_offs.iter().filter(|(name, _)| {
+            !DEPENDENT_ATTRIBUTES
+                .iter()
+                .any(|(attribute, ..)| attribute == name)
+        })
+    }
+
     #[must_use]
     pub(super) fn validate(&self) -> Vec<Option<String>> {
-        let validator = DependentAttributeConfigValidator::default();
         let mut messages = vec![];
-
-        macro_rules! attrs_limit {
-            ($attr:ident) => {
-                messages.extend(attribute_limit(
-                    self.$attr.0,
-                    self.$attr.1,
-                    validator.$attr,
-                    stringify!($attr),
-                ))
-            };
-            ($attr:ident, $($attrs:ident), +) => {
-                attrs_limit!($attr);
-                attrs_limit!($($attrs), +)
+        for (name, _, _, _, limits) in DEPENDENT_ATTRIBUTES {
+            let trade_off = self.trade_off(name);
+            let (lower, upper) = trade_off.bounds;
+            messages.extend(attribute_limit(lower, upper, limits, name));
+            if trade_off.source == "mutation_rate" {
+                messages.push(Some(format!(
+                    "Dependent attribute '{name}' needs a source gene that every bug has"
+                )));
             }
         }
-        attrs_limit!(
-            adult_age_bounds,
-            death_age_bounds,
-            eye_angle_bounds,
-            mouth_width_bounds,
-            hatch_size_bounds,
-            grab_strength_bounds,
-            base_defence_bounds
-        );
+        for (name, trade_off) in &self.trade_offs {
+            messages.extend(trade_off.validate(name));
+        }
         messages
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn old_bounds_keys_set_the_trade_off_bounds() {
+        let config: DependentAttributeConfig = serde_json::from_str(
+            r#"{"adult_age_bounds": [20.0, 50.0], "hatch_size_bounds": [15.0, 40.0]}"#,
+        )
+        .unwrap();
+        let defaults = DependentAttributeConfig::default();
+
+        assert_eq!(config.trade_off("adult_age").bounds, (20.0, 50.0));
+        assert_eq!(config.trade_off("hatch_size").bounds, (15.0, 40.0));
+        assert_eq!(
+            config.trade_off("adult_age").source,
+            defaults.trade_off("adult_age").source
+        );
+        assert_eq!(
+            config.trade_off("death_age"),
+            defaults.trade_off("death_age")
+        );
+        assert_eq!(config.trade_offs.len(), DEPENDENT_ATTRIBUTES.len());
+        assert_eq!(config.extra_trade_offs().count(), 0);
+        assert!(config.validate().iter().all(Option::is_none));
+    }
+
+    #[test]
+    fn configured_trade_offs_replace_old_bounds_and_defaults() {
+        let config: DependentAttributeConfig = serde_json::from_str(
+            r#"{
+                "adult_age_bounds": [20.0, 50.0],
+                "trade_offs": {
+                    "adult_age": {"source": "max_size", "curve": "linear", "bounds": [25.0, 55.0]},
+                    "stamina": {"source": "top_speed", "curve": "exponential", "bounds": [1.0, 4.0]}
+                }
+            }"#,
+        )
+        .unwrap();
+
+        let adult_age = config.trade_off("adult_age");
+        assert_eq!(adult_age.source, "max_size");
+        assert_eq!(adult_age.curve, TradeOffCurve::Linear);
+        assert_eq!(adult_age.bounds, (25.0, 55.0));
+        let extra: Vec<&String> = config.extra_trade_offs().map(|(name, _)| name).collect();
+        assert_eq!(extra, ["stamina"]);
+        assert!(config.validate().iter().all(Option::is_none));
+    }
+}
@@ -4,6 +4,7 @@ use std::time::Duration;
 mod attr_config;
 mod validators;
 
+pub use attr_config::{ChromosomeConfig, ChromosomeKind, TradeOffConfig, TradeOffCurve, GENES};
 use bevy_core_pipeline::clear_color::ClearColor;
 use bevy_render::color::Color;
 use derive_getters::Getters;
@@ -18,8 +19,8 @@ pub const PAN_SPEED: f32 = 1000.0;
 pub const ZOOM_SPEED: f32 = 0.1;
 
 // Bugs
-pub const INPUT_NEURONS: usize = 19;
-pub const OUTPUT_NEURONS: usize = 9;
+pub const INPUT_NEURONS: usize = 23;
+pub const OUTPUT_NEURONS: usize = 10;
 pub const EATING_MULTIPLIER: f32 = 20.0;
 pub const CORE_MULTIPLIER: usize = 100;
 pub const HEALTH_MULTIPLIER: usize = 20;
@@ -42,6 +43,7 @@ pub const WANT_TO_GROWN_INDEX: usize = 5;
 pub const WANT_TO_GRAB_INDEX: usize = 6;
 pub const DIGEST_FOOD_INDEX: usize = 7;
 pub const ATTACK_INDEX: usize = 8;
+pub const FEED_INDEX: usize = 9;
 
 // Inputs
 pub const CONSTANT_INDEX: usize = 0;
@@ -63,6 +65,47 @@ pub const MEAT_DIST_SCORE_INDEX: usize = 15;
 pub const HEARTBEAT_INDEX: usize = 16;
 pub const INTERNAL_TIMER_INDEX: usize = 17;
 pub const FULLNESS_INDEX: usize = 18;
+pub const SEASON_INDEX: usize = 19;
+pub const DAYLIGHT_INDEX: usize = 20;
+pub const PLANT_TYPE_INDEX: usize = 21;
+pub const OFFSPRING_VISIBLE_INDEX: usize = 22;
+
+// Neuron names, inputs followed by outputs
+pub const NEURON_NAMES: [&str; INPUT_NEURONS + OUTPUT_NEURONS] = [
+    "Constant",
+    "Movement",
+    "Rotation",
+    "Energy",
+    "Health",
+    "Age",
+    "Visible bugs",
+    "Bug angle",
+    "Bug distance",
+    "Bug species",
+    "Visible plants",
+    "Plant angle",
+    "Plant distance",
+    "Visible meat",
+    "Meat angle",
+    "Meat distance",
+    "Heartbeat",
+    "Internal timer",
+    "Fullness",
+    "Season",
+    "Daylight",
+    "Plant type",
+    "Offspring visible",
+    "Movement",
+    "Rotation",
+    "Reproduce",
+    "Eat",
+    "Reset timer",
+    "Want to grow",
+    "Want to grab",
+    "Digest",
+    "Attack",
+    "Feed",
+];
 
 // Other
 pub const GENERATION_SWITCH: usize = 5;
@@ -80,11 +123,113 @@ impl DistributionConfig {
     }
 }
 
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum SpawnerMovementConfig {
+    #[default]
+    Fixed,
+    Orbit {
+        radius: f32,
+        period: f32,
+    },
+    Drift {
+        direction: (f32, f32),
+        speed: f32,
+        distance: f32,
+    },
+    Waypoints {
+        points: Vec<(f32, f32)>,
+        speed: f32,
+    },
+}
+
+impl SpawnerMovementConfig {
+    pub fn validate(&self) -> Vec<Option<String>> {
+        match self {
+            Self::Fixed => vec![],
+            Self::Orbit { radius, period } => vec![
+                validators::min_value(0.0, *radius, "spawners.movement.radius"),
+                validators::min_value(1.0, *period, "spawners.movement.period"),
+            ],
+            Self::Drift {
+                direction,
+                speed,
+                distance,
+            } => vec![
+                (direction.0 == 0.0 && direction.1 == 0.0).then(|| {
+                    "The value 'spawners.movement.direction' must be non-zero.".to_string()
+                }),
+                validators::min_value(0.0, *speed, "spawners.movement.speed"),
+                validators::min_value(0.0, *distance, "spawners.movement.distance"),
+            ],
+            Self::Waypoints { points, speed } => vec![
+                points
+                    .is_empty()
+                    .then(|| "The value 'spawners.movement.points' must not be empty.".to_string()),
+                validators::min_value(0.0, *speed, "spawners.movement.speed"),
+            ],
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct SpawnerScheduleConfig {
+    pub period: f32,
+    pub active_fraction: f32,
+    pub offset: f32,
+}
+
+impl SpawnerScheduleConfig {
+    pub fn validate(&self) -> Vec<Option<String>> {
+        vec![
+            validators::min_value(1.0, self.period, "spawners.schedule.period"),
+            validators::between(
+                self.active_fraction,
+                0.0,
+                1.0,
+                "spawners.schedule.active_fraction",
+            ),
+            validators::min_value(0.0, self.offset, "spawners.schedule.offset"),
+        ]
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum SpawnerRole {
+    Plants,
+    Bugs,
+    #[default]
+    Both,
+}
+
+impl SpawnerRole {
+    pub const fn spawns_plants(self) -> bool {
+        matches!(self, Self::Plants | Self::Both)
+    }
+
+    pub const fn spawns_bugs(self) -> bool {
+        matches!(self, Self::Bugs | Self::Both)
+    }
+}
+
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct SpawnerConfig {
     pub centre: (f32, f32),
     pub radius: f32,
     pub dist: DistributionConfig,
+    #[serde(default)]
+    pub role: SpawnerRole,
+    #[serde(default)]
+    pub plant: Option<PlantConfig>,
+    #[serde(default)]
+    pub plant_rate_limit: Option<f32>,
+    #[serde(default)]
+    pub species: Vec<usize>,
+    #[serde(default)]
+    pub movement: SpawnerMovementConfig,
+    #[serde(default)]
+    pub schedule: Option<SpawnerScheduleConfig>,
 }
 
 impl SpawnerConfig {
@@ -93,8 +238,52 @@ impl SpawnerConfig {
             centre,
             radius,
             dist,
+            role: SpawnerRole::Both,
+            plant: None,
+            plant_rate_limit: None,
+            species: vec![],
+            movement: SpawnerMovementConfig::Fixed,
+            schedule: None,
         }
     }
+
+    pub fn validate(&self) -> Vec<Option<String>> {
+        let mut messages = vec![validators::min_value(0.0, self.radius, "spawners.radius")];
+        if let Some(plant) = &self.plant {
+            messages.extend(plant.validate());
+        }
+        if let Some(rate_limit) = self.plant_rate_limit {
+            messages.push(validators::min_value(
+                0.0,
+                rate_limit,
+                "spawners.plant_rate_limit",
+            ));
+        }
+        messages.extend(self.movement.validate());
+        if let Some(schedule) = &self.schedule {
+            messages.extend(schedule.validate());
+        }
+        messages
+    }
+}
+
+/// Describes why a starting synapse does not join an input neuron to an output neuron.
+pub fn starting_synapse_issue((from, to): (usize, usize)) -> Option<String> {
+    let outputs = INPUT_NEURONS..INPUT_NEURONS + OUTPUT_NEURONS;
+    if from >= INPUT_NEURONS {
+        Some(format!(
+            "Starting synapse ({from}, {to}) must start at an input neuron, below {INPUT_NEURONS}."
+        ))
+    } else if !outputs.contains(&to) {
+        Some(format!(
+            "Starting synapse ({from}, {to}) from '{}' must end at an output neuron, between {} and {}.",
+            NEURON_NAMES[from],
+            outputs.start,
+            outputs.end - 1
+        ))
+    } else {
+        None
+    }
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -111,27 +300,96 @@ pub struct WorldConfig {
     pub max_translation: f32,
     pub translation_cost: f32,
     pub unit_size_cost: f32,
+    /// Extra cost per unit of size for each unit of the body density gene above 1.
+    #[serde(default = "WorldConfig::default_trait_cost")]
+    pub density_cost: f32,
+    /// Extra cost per unit of size for each unit of the top speed gene above 1.
+    #[serde(default = "WorldConfig::default_trait_cost")]
+    pub top_speed_cost: f32,
+    /// Extra cost per unit of size for each unit of the turn rate gene above 1.
+    #[serde(default = "WorldConfig::default_trait_cost")]
+    pub turn_rate_cost: f32,
+    /// Extra cost per unit of size for each unit of the venom gene.
+    #[serde(default = "WorldConfig::default_trait_cost")]
+    pub venom_cost: f32,
+    /// Extra cost per unit of size for each unit of the spines gene.
+    #[serde(default = "WorldConfig::default_trait_cost")]
+    pub spines_cost: f32,
+    /// The fraction of speed lost for each unit of the armour gene.
+    #[serde(default = "WorldConfig::default_armour_speed_penalty")]
+    pub armour_speed_penalty: f32,
+    /// Seconds over which venom deals its damage.
+    #[serde(default = "WorldConfig::default_poison_duration")]
+    pub poison_duration: f32,
     pub world_energy: usize,
     pub mutation_probability: f32,
     pub healing_rate: usize,
     pub cost_of_thought: f32,
     pub cost_of_grab: f32,
     pub cost_of_lay: f32,
+    /// Removes the synapses that cannot affect an output from each brain as its egg hatches.
+    /// Inactive synapses that still reach an output are kept, as they carry values.
+    #[serde(default)]
+    pub prune_brains_on_hatch: bool,
     pub plant: PlantConfig,
+    #[serde(default)]
+    pub plant_species: Vec<PlantConfig>,
+    #[serde(default)]
+    pub seeding: SeedingConfig,
     pub meat: MeatConfig,
+    #[serde(default)]
+    pub climate: ClimateConfig,
+    #[serde(default)]
+    pub parental_care: ParentalCareConfig,
     pub spawners: Vec<SpawnerConfig>,
     pub attributes: attr_config::AttributeConfig,
     pub dependent_attributes: attr_config::DependentAttributeConfig,
     pub brain_mutations: BrainMutationConfig,
+    /// Every kind of plant in the world, indexed by plant type id, built when the config is
+    /// initialised.
+    #[serde(skip)]
+    plant_types: Vec<PlantConfig>,
 }
 
 impl WorldConfig {
+    const fn default_trait_cost() -> f32 {
+        0.25
+    }
+
+    const fn default_armour_speed_penalty() -> f32 {
+        0.5
+    }
+
+    const fn default_poison_duration() -> f32 {
+        5.0
+    }
+
     pub fn global() -> &'static Self {
         WORLD_CONFIG_INSTANCE
             .get()
             .expect("World config is not initialized")
     }
 
+    /// The main plant config comes first, then the extra species, then any spawner overrides.
+    fn build_plant_types(&mut self) {
+        self.plant_types = std::iter::once(&self.plant)
+            .chain(self.plant_species.iter())
+            .chain(self.spawners.iter().filter_map(|s| s.plant.as_ref()))
+            .cloned()
+            .collect();
+    }
+
+    /// Every kind of plant in the world, indexed by plant type id.
+    pub fn plant_types(&self) -> &[PlantConfig] {
+        &self.plant_types
+    }
+
+    /// The plant type with this id, or the base plant for an id this config does not have, such
+    /// as one from a save with more plant species.
+    pub fn plant_type(&self, id: usize) -> &PlantConfig {
+        self.plant_types.get(id).unwrap_or(&self.plant)
+    }
+
     pub fn from_config() -> Result<Self, Vec<String>> {
         let world_config: Self = confy::load_path("./config/genesis.toml").unwrap();
         world_config.validate()?;
@@ -141,6 +399,13 @@ impl WorldConfig {
     fn validate(&self) -> Result<(), Vec<String>> {
         let mut messages = vec![
             validators::min_value(0.0, self.unit_size_cost, "unit_size_cost"),
+            validators::min_value(0.0, self.density_cost, "density_cost"),
+            validators::min_value(0.0, self.top_speed_cost, "top_speed_cost"),
+            validators::min_value(0.0, self.turn_rate_cost, "turn_rate_cost"),
+            validators::min_value(0.0, self.venom_cost, "venom_cost"),
+            validators::min_value(0.0, self.spines_cost, "spines_cost"),
+            validators::between(self.armour_speed_penalty, 0.0, 1.0, "armour_speed_penalty"),
+            validators::between(self.poison_duration, 0.1, 60.0, "poison_duration"),
             validators::between(self.max_rotation, 5.0, 40.0, "max_rotation"),
             validators::between(self.max_translation, 100.0, 1000.0, "max_translation"),
             validators::between(self.rotation_cost, 0.0, 10.0, "rotation_cost"),
@@ -156,8 +421,38 @@ impl WorldConfig {
                 "start_num",
             ),
         ];
+        messages.extend(
+            self.starting_synapses
+                .iter()
+                .map(|synapse| starting_synapse_issue(*synapse)),
+        );
         messages.extend(self.plant.validate());
+        for species in &self.plant_species {
+            messages.extend(species.validate());
+        }
+        messages.extend(self.seeding.validate());
         messages.extend(self.meat.validate());
+        messages.extend(self.climate.validate());
+        messages.extend(self.parental_care.validate());
+        for spawner in &self.spawners {
+            messages.extend(spawner.validate());
+            if spawner
+                .species
+                .iter()
+                .any(|id| *id > self.plant_species.len())
+            {
+                messages.push(Some(format!(
+                    "Spawner species must be between 0 and {}.",
+                    self.plant_species.len()
+                )));
+            }
+        }
+        if !self.spawners.iter().any(|s| s.role.spawns_plants()) {
+            messages.push(Some("At least one spawner must spawn plants.".to_string()));
+        }
+        if !self.spawners.iter().any(|s| s.role.spawns_bugs()) {
+            messages.push(Some("At least one spawner must spawn bugs.".to_string()));
+        }
         messages.extend(self.attributes.validate());
         messages.extend(self.dependent_attributes.validate());
         messages.extend(self.brain_mutations.validate());
@@ -174,11 +469,11 @@ impl Default for WorldConfig {
     fn default() -> Self {
         let dist = DistributionConfig::new("normal".to_string(), 0.0, 1.0);
         let spawner = SpawnerConfig::new((0.0, 0.0), 500.0, dist);
-        Self {
+        let mut config = Self {
             start_num: 0,
             minimum_number: 0,
             energy_floor: 100000,
-            starting_synapses: vec![(0, 19), (11, 20), (18, 26)],
+            starting_synapses: vec![(0, 23), (11, 24), (18, 30)],
             mutations: 3,
             start_energy: 10000,
             lowest_energy_limit: 20000,
@@ -187,19 +482,34 @@ impl Default for WorldConfig {
             max_translation: 400.0,
             translation_cost: 1.5,
             unit_size_cost: 2.00,
+            density_cost: Self::default_trait_cost(),
+            top_speed_cost: Self::default_trait_cost(),
+            turn_rate_cost: Self::default_trait_cost(),
+            venom_cost: Self::default_trait_cost(),
+            spines_cost: Self::default_trait_cost(),
+            armour_speed_penalty: Self::default_armour_speed_penalty(),
+            poison_duration: Self::default_poison_duration(),
             world_energy: 1000000,
             mutation_probability: 0.1,
             healing_rate: 10,
             cost_of_thought: 0.8,
             cost_of_grab: 2.0,
             cost_of_lay: 5.0,
+            prune_brains_on_hatch: false,
             plant: PlantConfig::default(),
+            plant_species: vec![PlantConfig::berry_bush()],
+            seeding: SeedingConfig::default(),
             meat: MeatConfig::default(),
+            climate: ClimateConfig::default(),
+            parental_care: ParentalCareConfig::default(),
             spawners: vec![spawner],
             attributes: attr_config::AttributeConfig::default(),
             dependent_attributes: attr_config::DependentAttributeConfig::default(),
             brain_mutations: BrainMutationConfig::default(),
-        }
+            plant_types: vec![],
+        };
+        config.build_plant_types();
+        config
     }
 }
 
@@ -213,8 +523,8 @@ pub struct EnergyLimitConfig {
 impl EnergyLimitConfig {
     pub fn new(config: &WorldConfig) -> Self {
         let e = config.lowest_energy_limit as f32;
-        let h = config.dependent_attributes.hatch_size_bounds.0;
-        let m = config.attributes.max_size.1;
+        let h = config.dependent_attributes.trade_off("hatch_size").bounds.0;
+        let m = config.attributes.max_size.max;
 
         let a = (e / h) * (10.0 * (m - h) / m);
         let b = 5.0f32.mul_add(m, -10.0 * h) / (h * m);
@@ -237,13 +547,14 @@ impl EnergyLimitConfig {
 pub static ENERGY_LIMIT_INSTANCE: OnceCell<EnergyLimitConfig> = OnceCell::new();
 
 pub fn initialize_configs(input_config: Option<WorldConfig>) {
-    let config = input_config.map_or_else(
+    let mut config = input_config.map_or_else(
         || match WorldConfig::from_config() {
             Ok(c) => c,
             Err(e) => panic!("Config validation failed. Issues are: {e:?}"),
         },
         |c| c,
     );
+    config.build_plant_types();
     let energy_limit_config = EnergyLimitConfig::new(&config);
     _ = WORLD_CONFIG_INSTANCE.set(config);
     _ = ENERGY_LIMIT_INSTANCE.set(energy_limit_config);
@@ -258,6 +569,18 @@ pub struct BrainMutationConfig {
     synapse_weight: f32,
     deactivate_synapse: f32,
     add_synapse: f32,
+    /// Mean of the Poisson distributed number of brain mutations in each egg.
+    #[serde(default = "BrainMutationConfig::default_mean_mutations")]
+    mean_mutations: f32,
+    /// Weights and biases are kept within plus or minus this value.
+    #[serde(default = "BrainMutationConfig::default_max_weight")]
+    max_weight: f32,
+    /// Standard deviation of the offset added when a weight or bias is perturbed.
+    #[serde(default = "BrainMutationConfig::default_mutation_sigma")]
+    mutation_sigma: f32,
+    /// Chance that a mutated weight or bias is replaced with a fresh random value instead.
+    #[serde(default)]
+    replace_probability: f32,
 }
 
 impl Default for BrainMutationConfig {
@@ -270,11 +593,27 @@ impl Default for BrainMutationConfig {
             synapse_weight: 0.5,
             deactivate_synapse: 0.1,
             add_synapse: 0.1,
+            mean_mutations: Self::default_mean_mutations(),
+            max_weight: Self::default_max_weight(),
+            mutation_sigma: Self::default_mutation_sigma(),
+            replace_probability: 0.0,
         }
     }
 }
 
 impl BrainMutationConfig {
+    const fn default_mean_mutations() -> f32 {
+        0.1
+    }
+
+    const fn default_max_weight() -> f32 {
+        1.0
+    }
+
+    const fn default_mutation_sigma() -> f32 {
+        1.0
+    }
+
     pub fn validate(&self) -> Vec<Option<String>> {
         let mut messages = vec![];
         macro_rules! probabilities {
@@ -314,6 +653,30 @@ impl BrainMutationConfig {
             1.0,
             "Total Brain Probabilities",
         ));
+        messages.push(validators::between(
+            self.mean_mutations,
+            0.0,
+            10.0,
+            "mean_mutations",
+        ));
+        messages.push(validators::between(
+            self.max_weight,
+            1.0,
+            genesis_newtype::MAX_WEIGHT_LIMIT,
+            "max_weight",
+        ));
+        messages.push(validators::between(
+            self.mutation_sigma,
+            0.0,
+            10.0,
+            "mutation_sigma",
+        ));
+        messages.push(validators::between(
+            self.replace_probability,
+            0.0,
+            1.0,
+            "replace_probability",
+        ));
 
         messages
     }
@@ -325,6 +688,14 @@ pub struct PlantConfig {
     pub toughness: f32,
     pub size_range: (f32, f32),
     pub density: f32,
+    #[serde(default = "PlantConfig::default_tint")]
+    pub tint: (f32, f32, f32),
+    #[serde(default = "PlantConfig::default_max_size")]
+    pub max_size: f32,
+    #[serde(default = "PlantConfig::default_growth_rate")]
+    pub growth_rate: f32,
+    #[serde(default = "PlantConfig::default_regrows")]
+    pub regrows: bool,
 }
 
 impl Default for PlantConfig {
@@ -334,16 +705,60 @@ impl Default for PlantConfig {
             toughness: 5.0,
             size_range: (10.0, 30.0),
             density: 10.0,
+            tint: Self::default_tint(),
+            max_size: Self::default_max_size(),
+            growth_rate: Self::default_growth_rate(),
+            regrows: Self::default_regrows(),
         }
     }
 }
 
 impl PlantConfig {
+    const fn default_tint() -> (f32, f32, f32) {
+        (0.0, 1.0, 0.0)
+    }
+
+    const fn default_max_size() -> f32 {
+        30.0
+    }
+
+    const fn default_growth_rate() -> f32 {
+        1.0
+    }
+
+    const fn default_regrows() -> bool {
+        true
+    }
+
+    /// A slow growing, tough and energy rich plant that does not regrow once grazed.
+    pub const fn berry_bush() -> Self {
+        Self {
+            energy_density: 600,
+            toughness: 10.0,
+            size_range: (3.0, 6.0),
+            density: 15.0,
+            tint: (0.6, 0.2, 0.8),
+            max_size: 20.0,
+            growth_rate: 0.5,
+            regrows: false,
+        }
+    }
+
     pub fn validate(&self) -> Vec<Option<String>> {
         let mut messages = vec![
             validators::min_value(1, self.energy_density, "plant.energy_density"),
             validators::between(self.toughness, 1.0, 100.0, "plant.toughness"),
             validators::between(self.density, 1.0, 100.0, "plant.density"),
+            validators::between(self.tint.0, 0.0, 1.0, "plant.tint"),
+            validators::between(self.tint.1, 0.0, 1.0, "plant.tint"),
+            validators::between(self.tint.2, 0.0, 1.0, "plant.tint"),
+            validators::min_value(0.0, self.growth_rate, "plant.growth_rate"),
+            validators::low_high(
+                self.size_range.1,
+                self.max_size,
+                "plant.size_range",
+                "plant.max_size",
+            ),
         ];
         messages.push(validators::low_high_tuple(
             self.size_range,
@@ -353,6 +768,82 @@ impl PlantConfig {
     }
 }
 
+#[derive(Debug, Serialize, Deserialize, Getters, Clone)]
+pub struct SeedingConfig {
+    pub enabled: bool,
+    pub min_plants: usize,
+    pub seed_size: f32,
+    pub seed_probability: f32,
+    pub dispersal_radius: f32,
+    pub crowding_radius: f32,
+    pub max_neighbours: usize,
+}
+
+impl Default for SeedingConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            min_plants: 20,
+            seed_size: 0.8,
+            seed_probability: 0.1,
+            dispersal_radius: 150.0,
+            crowding_radius: 50.0,
+            max_neighbours: 4,
+        }
+    }
+}
+
+impl SeedingConfig {
+    pub fn validate(&self) -> Vec<Option<String>> {
+        vec![
+            validators::between(self.seed_size, 0.0, 1.0, "seeding.seed_size"),
+            validators::between(self.seed_probability, 0.0, 1.0, "seeding.seed_probability"),
+            validators::min_value(1.0, self.dispersal_radius, "seeding.dispersal_radius"),
+            validators::min_value(1.0, self.crowding_radius, "seeding.crowding_radius"),
+            validators::min_value(1, self.max_neighbours, "seeding.max_neighbours"),
+        ]
+    }
+}
+
+/// Lets parents guard their eggs and feed their offspring.
+///
+/// An egg ages `guarded_hatch_speed` times as fast while its parent is within `care_range`, and
+/// a parent using its feed output passes up to `feed_rate` energy a second to its nearest egg
+/// or juvenile within the same range. Eggs take no more than their hatch size can hold.
+#[derive(Debug, Serialize, Deserialize, Getters, Clone)]
+pub struct ParentalCareConfig {
+    pub enabled: bool,
+    pub care_range: f32,
+    pub guarded_hatch_speed: f32,
+    pub feed_rate: f32,
+}
+
+impl Default for ParentalCareConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            care_range: 80.0,
+            guarded_hatch_speed: 1.5,
+            feed_rate: 50.0,
+        }
+    }
+}
+
+impl ParentalCareConfig {
+    pub fn validate(&self) -> Vec<Option<String>> {
+        vec![
+            validators::min_value(1.0, self.care_range, "parental_care.care_range"),
+            validators::between(
+                self.guarded_hatch_speed,
+                1.0,
+                10.0,
+                "parental_care.guarded_hatch_speed",
+            ),
+            validators::min_value(0.0, self.feed_rate, "parental_care.feed_rate"),
+        ]
+    }
+}
+
 #[derive(Debug, Serialize, Deserialize, Getters, Clone)]
 pub struct MeatConfig {
     pub energy_density: usize,
@@ -382,3 +873,60 @@ impl MeatConfig {
         ]
     }
 }
+
+/// Seasons and the day/night cycle. Missing keys take their default values.
+#[derive(Debug, Serialize, Deserialize, Getters, Clone)]
+#[serde(default)]
+pub struct ClimateConfig {
+    pub season_length: f32,
+    pub day_length: f32,
+    pub plant_spawn_amplitude: f32,
+    pub plant_size_amplitude: f32,
+    pub rot_rate_amplitude: f32,
+    pub night_sight: f32,
+    /// Whether bugs sense the season and daylight. When off, the season and daylight input
+    /// neurons stay in every brain but always read 0, so brains keep the same shape.
+    pub brain_inputs: bool,
+}
+
+impl Default for ClimateConfig {
+    fn default() -> Self {
+        Self {
+            season_length: 600.0,
+            day_length: 120.0,
+            plant_spawn_amplitude: 0.5,
+            plant_size_amplitude: 0.2,
+            rot_rate_amplitude: 0.5,
+            night_sight: 0.6,
+            brain_inputs: true,
+        }
+    }
+}
+
+impl ClimateConfig {
+    pub fn validate(&self) -> Vec<Option<String>> {
+        vec![
+            validators::min_value(1.0, self.season_length, "climate.season_length"),
+            validators::min_value(1.0, self.day_length, "climate.day_length"),
+            validators::between(
+                self.plant_spawn_amplitude,
+                0.0,
+                1.0,
+                "climate.plant_spawn_amplitude",
+            ),
+            validators::between(
+                self.plant_size_amplitude,
+                0.0,
+                0.9,
+                "climate.plant_size_amplitude",
+            ),
+            validators::between(
+                self.rot_rate_amplitude,
+                0.0,
+                1.0,
+                "climate.rot_rate_amplitude",
+            ),
+            validators::between(self.night_sight, 0.0, 1.0, "climate.night_sight"),
+        ]
+    }
+}
@@ -0,0 +1,446 @@
+use std::collections::HashMap;
+
+use bevy::prelude::{error, Query, Res, ResMut, Resource};
+use derive_getters::Getters;
+use genesis_components::{body::Vitality, eat::Stomach, BurntEnergy};
+use genesis_config as config;
+use genesis_ecosystem as ecosystem;
+
+/// A movement of energy between two of the stores the auditor sums.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum EnergyFlow {
+    /// Ecosystem to plants, when plants spawn, seed or grow.
+    Planted,
+    /// Ecosystem to eggs, when new organisms are spawned.
+    Spawned,
+    /// Food to stomachs.
+    Eaten,
+    /// Stomachs to vitality.
+    Digested,
+    /// Vitality to burnt energy, paying for behaviours.
+    Spent,
+    /// Digestion costs and undigested food, to burnt energy.
+    Wasted,
+    /// Burnt energy and unusable leftovers back to the ecosystem.
+    Burnt,
+    /// Health lost to attacks, back to the ecosystem.
+    Injured,
+    /// Meat back to the ecosystem.
+    Rotted,
+    /// Vitality to eggs.
+    Laid,
+    /// Eggs to vitality.
+    Hatched,
+    /// Parent vitality to its eggs and juveniles.
+    Fed,
+    /// Dead bugs and eggs to meat, or killed bugs back to the ecosystem.
+    Killed,
+}
+
+impl EnergyFlow {
+    pub const ALL: [Self; 13] = [
+        Self::Planted,
+        Self::Spawned,
+        Self::Eaten,
+        Self::Digested,
+        Self::Spent,
+        Self::Wasted,
+        Self::Burnt,
+        Self::Injured,
+        Self::Rotted,
+        Self::Laid,
+        Self::Hatched,
+        Self::Fed,
+        Self::Killed,
+    ];
+
+    pub const fn as_str(&self) -> &'static str {
+        match self {
+            Self::Planted => "planted",
+            Self::Spawned => "spawned",
+            Self::Eaten => "eaten",
+            Self::Digested => "digested",
+            Self::Spent => "spent",
+            Self::Wasted => "wasted",
+            Self::Burnt => "burnt",
+            Self::Injured => "injured",
+            Self::Rotted => "rotted",
+            Self::Laid => "laid",
+            Self::Hatched => "hatched",
+            Self::Fed => "fed",
+            Self::Killed => "killed",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FlowRecord {
+    pub flow: EnergyFlow,
+    pub system: &'static str,
+    pub amount: usize,
+}
+
+/// Running totals of every energy flow, plus the flows recorded since the last audit.
+///
+/// Flows can also be split into named parts, such as the behaviour a cost was spent on.
+#[derive(Resource, Debug, Default)]
+pub struct EnergyLedger {
+    totals: [usize; EnergyFlow::ALL.len()],
+    parts: HashMap<(EnergyFlow, &'static str), usize>,
+    pending: Vec<FlowRecord>,
+}
+
+impl EnergyLedger {
+    pub fn record(&mut self, flow: EnergyFlow, system: &'static str, amount: usize) {
+        if amount == 0 {
+            return;
+        }
+        self.totals[flow as usize] += amount;
+        self.pending.push(FlowRecord {
+            flow,
+            system,
+            amount,
+        });
+    }
+
+    pub fn record_part(
+        &mut self,
+        flow: EnergyFlow,
+        system: &'static str,
+        part: &'static str,
+        amount: usize,
+    ) {
+        if amount == 0 {
+            return;
+        }
+        *self.parts.entry((flow, part)).or_default() += amount;
+        self.record(flow, system, amount);
+    }
+
+    pub const fn total(&self, flow: EnergyFlow) -> usize {
+        self.totals[flow as usize]
+    }
+
+    pub fn part_total(&self, flow: EnergyFlow, part: &'static str) -> usize {
+        self.parts.get(&(flow, part)).copied().unwrap_or_default()
+    }
+
+    fn end_tick(&mut self) -> Vec<FlowRecord> {
+        std::mem::take(&mut self.pending)
+    }
+}
+
+/// Energy held in each store at the time of an audit.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct EnergyStores {
+    pub ecosystem: usize,
+    pub food: usize,
+    pub eggs: usize,
+    pub vitality: usize,
+    pub stomachs: usize,
+    pub burnt: usize,
+}
+
+impl EnergyStores {
+    pub const fn total(&self) -> usize {
+        self.ecosystem + self.food + self.eggs + self.vitality + self.stomachs + self.burnt
+    }
+}
+
+#[derive(Resource, Debug, Default, Getters)]
+pub struct EnergyAudit {
+    stores: EnergyStores,
+    discrepancy: isize,
+    /// Panic instead of logging when energy is not conserved, for tests.
+    #[getter(skip)]
+    panic_on_leak: bool,
+}
+
+impl EnergyAudit {
+    #[cfg(test)]
+    pub fn strict() -> Self {
+        Self {
+            panic_on_leak: true,
+            ..Self::default()
+        }
+    }
+
+    /// Records a new set of stores and returns how much the discrepancy changed since the last audit.
+    fn update(&mut self, stores: EnergyStores, world_energy: usize) -> isize {
+        let discrepancy = stores.total() as isize - world_energy as isize;
+        let change = discrepancy - self.discrepancy;
+        self.stores = stores;
+        self.discrepancy = discrepancy;
+        change
+    }
+
+    pub const fn is_balanced(&self) -> bool {
+        self.discrepancy == 0
+    }
+}
+
+fn suspects(records: &[FlowRecord]) -> String {
+    let mut systems: Vec<String> = records
+        .iter()
+        .map(|record| format!("{} ({})", record.system, record.flow.as_str()))
+        .collect();
+    systems.sort_unstable();
+    systems.dedup();
+    if systems.is_empty() {
+        "a system that records no flows".to_string()
+    } else {
+        systems.join(", ")
+    }
+}
+
+/// Sums every store and reports a leak with the flows recorded since the previous audit, so
+/// running it after every system that records flows narrows the suspects to one frame.
+pub fn audit_energy_system(
+    mut audit: ResMut<EnergyAudit>,
+    mut ledger: ResMut<EnergyLedger>,
+    ecosystem: Res<ecosystem::Ecosystem>,
+    food_query: Query<&ecosystem::Food>,
+    egg_query: Query<&ecosystem::EggEnergy>,
+    vitality_query: Query<&Vitality>,
+    stomach_query: Query<&Stomach>,
+    burnt_query: Query<&BurntEnergy>,
+) {
+    let stores = EnergyStores {
+        ecosystem: ecosystem.available_energy().amount(),
+        food: food_query.iter().map(|food| food.energy().amount()).sum(),
+        eggs: egg_query.iter().map(|egg| egg.0.amount()).sum(),
+        vitality: vitality_query.iter().map(Vitality::total_energy).sum(),
+        stomachs: stomach_query.iter().map(Stomach::stored_energy).sum(),
+        burnt: burnt_query.iter().map(|burnt| burnt.amount()).sum(),
+    };
+    let world_energy = config::WorldConfig::global().world_energy;
+    let change = audit.update(stores, world_energy);
+    let records = ledger.end_tick();
+
+    if change != 0 {
+        let message = format!(
+            "Energy not conserved: stores hold {} but world energy is {world_energy} \
+            (changed by {change} since the last audit). Energy was moved by {}.",
+            stores.total(),
+            suspects(&records),
+        );
+        assert!(!audit.panic_on_leak, "{message}");
+        if cfg!(debug_assertions) {
+            error!("{message}");
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::{
+        asset::AssetPlugin,
+        core::CorePlugin,
+        ecs::system::System,
+        hierarchy::HierarchyPlugin,
+        prelude::{
+            App, AssetServer, Commands, CoreStage, Entity, EventWriter, IntoSystem, Vec3, With,
+            World,
+        },
+        time::TimePlugin,
+        transform::TransformPlugin,
+    };
+    use bevy_rapier2d::prelude::{NoUserData, RapierPhysicsPlugin};
+    use genesis_attributes::{Dna, Genome};
+    use genesis_components::{
+        mind, time::SimulationTime, DeadEggEvent, Egg, Generation, PlantType,
+    };
+    use genesis_spawners::Spawners;
+    use iyes_loopless::prelude::*;
+
+    use super::*;
+    use crate::{
+        behaviour::{eating, laying, metabolism},
+        climate, lifecycle, spawning, statistics,
+    };
+
+    #[test]
+    fn ledger_totals_flows() {
+        let mut ledger = EnergyLedger::default();
+        ledger.record(EnergyFlow::Eaten, "eating_system", 10);
+        ledger.record(EnergyFlow::Eaten, "eating_system", 5);
+        ledger.record(EnergyFlow::Rotted, "rot_meat_system", 0);
+        ledger.record_part(EnergyFlow::Spent, "energy_return_system", "ThinkingSum", 3);
+
+        assert_eq!(ledger.total(EnergyFlow::Eaten), 15);
+        assert_eq!(ledger.total(EnergyFlow::Rotted), 0);
+        assert_eq!(ledger.total(EnergyFlow::Spent), 3);
+        assert_eq!(ledger.part_total(EnergyFlow::Spent, "ThinkingSum"), 3);
+        assert_eq!(ledger.part_total(EnergyFlow::Spent, "EatingSum"), 0);
+        assert_eq!(
+            suspects(&ledger.end_tick()),
+            "eating_system (eaten), energy_return_system (spent)"
+        );
+        assert!(ledger.end_tick().is_empty());
+    }
+
+    fn spawn_organisms_system(
+        mut commands: Commands,
+        asset_server: Res<AssetServer>,
+        genome: Res<Genome>,
+        mut ecosystem: ResMut<ecosystem::Ecosystem>,
+    ) {
+        let energy = ecosystem.request_energy(500).unwrap();
+        spawning::spawn_meat(&mut commands, &asset_server, energy, Vec3::ZERO, None);
+
+        let energy = ecosystem.request_energy(1000).unwrap();
+        let dna = Dna::new(&genome, &mut rand::thread_rng());
+        let mind = mind::Mind::minimal(config::INPUT_NEURONS, config::OUTPUT_NEURONS, &[]);
+        spawning::spawn_egg(
+            &mut commands,
+            &asset_server,
+            &genome,
+            energy,
+            Vec3::new(100.0, 0.0, 0.0),
+            dna,
+            mind,
+            Generation(0),
+            None,
+        );
+    }
+
+    fn kill_eggs_system(
+        mut ev_egg: EventWriter<DeadEggEvent>,
+        egg_query: Query<Entity, With<Egg>>,
+    ) {
+        for egg in egg_query.iter() {
+            ev_egg.send(DeadEggEvent(egg));
+        }
+    }
+
+    #[test]
+    fn audit_tracks_discrepancy() {
+        let world_energy = 100;
+        let balanced = EnergyStores {
+            ecosystem: 60,
+            vitality: 40,
+            ..EnergyStores::default()
+        };
+        let leaked = EnergyStores {
+            ecosystem: 50,
+            ..balanced
+        };
+        let mut audit = EnergyAudit::default();
+
+        assert_eq!(audit.update(balanced, world_energy), 0);
+        assert!(audit.is_balanced());
+        assert_eq!(audit.update(leaked, world_energy), -10);
+        assert_eq!(*audit.discrepancy(), -10);
+        assert_eq!(audit.update(leaked, world_energy), 0);
+        assert!(!audit.is_balanced());
+    }
+
+    #[test]
+    fn headless_sim_conserves_energy() {
+        config::initialize_configs(None);
+        let config_instance = config::WorldConfig::global();
+
+        let mut app = App::new();
+        app.add_plugin(CorePlugin::default())
+            .add_plugin(TimePlugin)
+            .add_plugin(TransformPlugin)
+            .add_plugin(HierarchyPlugin)
+            .add_plugin(AssetPlugin::default())
+            .add_plugin(RapierPhysicsPlugin::<NoUserData>::pixels_per_meter(1000.0))
+            .add_plugin(genesis_components::ComponentsPlugin)
+            .init_resource::<FixedTimesteps>()
+            .insert_resource(
+                Spawners::from_configs(
+                    &config_instance.spawners,
+                    config_instance.plant_species.len() + 1,
+                )
+                .unwrap(),
+            )
+            .insert_resource(spawning::PlantSizeRandomiser::new(
+                config_instance.plant_types(),
+            ))
+            .insert_resource(ecosystem::Ecosystem::new(config_instance.world_energy))
+            .insert_resource(mind::MindThresholds::new(&config_instance.brain_mutations))
+            .init_resource::<Genome>()
+            .init_resource::<SimulationTime>()
+            .init_resource::<climate::Climate>()
+            .init_resource::<statistics::CountStats>()
+            .init_resource::<statistics::BugPerformance>()
+            .init_resource::<statistics::FamilyTree>()
+            .init_resource::<statistics::FoodWeb>()
+            .init_resource::<EnergyLedger>()
+            .insert_resource(EnergyAudit::strict())
+            .add_fixed_timestep(config::BEHAVIOUR_TICK, "standard")
+            .add_system(spawning::spawn_plant_system)
+            .add_system(laying::spawn_egg_system)
+            .add_system(eating::eating_system)
+            .add_system(eating::digest_food_system)
+            .add_system(metabolism::energy_return_system)
+            .add_system(lifecycle::hatch_egg_system)
+            .add_system(lifecycle::rot_meat_system)
+            .add_system(lifecycle::kill_bug_system)
+            .add_system(lifecycle::kill_egg_system)
+            .add_system(kill_eggs_system)
+            .add_startup_system(spawn_organisms_system)
+            .add_system_to_stage(CoreStage::Last, audit_energy_system);
+
+        for _ in 0..20 {
+            app.update();
+            assert!(app.world.resource::<EnergyAudit>().is_balanced());
+        }
+
+        let plants = app.world.query::<&PlantType>().iter(&app.world).count();
+        assert!(plants > 0);
+        let ledger = app.world.resource::<EnergyLedger>();
+        assert!(ledger.total(EnergyFlow::Planted) > 0);
+        assert!(ledger.total(EnergyFlow::Killed) > 0);
+        assert!(ledger.total(EnergyFlow::Rotted) > 0);
+    }
+
+    #[test]
+    #[should_panic(expected = "Energy not conserved")]
+    fn strict_audit_panics_on_leak() {
+        config::initialize_configs(None);
+        let world_energy = config::WorldConfig::global().world_energy;
+
+        let mut world = World::new();
+        world.insert_resource(ecosystem::Ecosystem::new(world_energy));
+        world.init_resource::<EnergyLedger>();
+        world.insert_resource(EnergyAudit::strict());
+        let mut audit = IntoSystem::into_system(audit_energy_system);
+        audit.initialize(&mut world);
+        audit.run((), &mut world);
+        assert!(world.resource::<EnergyAudit>().is_balanced());
+
+        let _leaked = world
+            .resource_mut::<ecosystem::Ecosystem>()
+            .request_energy(10);
+        audit.run((), &mut world);
+    }
+
+    #[test]
+    #[should_panic(expected = "Energy was moved by leaking_system (eaten).")]
+    fn leaks_are_reported_with_only_the_flows_since_the_last_audit() {
+        config::initialize_configs(None);
+        let world_energy = config::WorldConfig::global().world_energy;
+
+        let mut world = World::new();
+        world.insert_resource(ecosystem::Ecosystem::new(world_energy));
+        world.init_resource::<EnergyLedger>();
+        world.insert_resource(EnergyAudit::strict());
+        let mut audit = IntoSystem::into_system(audit_energy_system);
+        audit.initialize(&mut world);
+        world
+            .resource_mut::<EnergyLedger>()
+            .record(EnergyFlow::Planted, "balanced_system", 10);
+        audit.run((), &mut world);
+
+        world
+            .resource_mut::<EnergyLedger>()
+            .record(EnergyFlow::Eaten, "leaking_system", 10);
+        let _leaked = world
+            .resource_mut::<ecosystem::Ecosystem>()
+            .request_energy(10);
+        audit.run((), &mut world);
+    }
+}
@@ -1,64 +1,211 @@
-use bevy::prelude::{Entity, EventWriter, Mut, Query, Res, ResMut, Transform, With, Without};
+use bevy::{
+    prelude::{Commands, Entity, EventWriter, Mut, Query, Res, ResMut, Transform, With, Without},
+    utils::HashMap,
+};
 use bevy_rapier2d::prelude::RapierContext;
-use genesis_attributes::{BaseAttack, BaseDefence};
+use genesis_attributes::{Armour, AttackCone, BaseAttack, BaseDefence, Spines, Venom};
 use genesis_components::{
-    body::{HealthEfficiency, Vitality},
+    body::{HealthEfficiency, Poisoned, Vitality},
     mind::MindOutput,
     time::AgeEfficiency,
-    DeadEggEvent, Egg, Size,
+    DeadEggEvent, Egg, Generation, Size,
 };
-use genesis_config::ATTACK_INDEX;
+use genesis_config::{WorldConfig, ATTACK_INDEX};
 use genesis_ecosystem::Ecosystem;
 use genesis_maths::angle_between;
+use iyes_loopless::prelude::FixedTimesteps;
+
+use crate::{
+    auditor::{EnergyFlow, EnergyLedger},
+    statistics::CombatStats,
+};
 
 pub type AttackingBug<'a> = (
+    Entity,
     &'a Transform,
     &'a MindOutput,
     &'a BaseAttack,
+    &'a AttackCone,
+    &'a Venom,
+    &'a Size,
+    &'a AgeEfficiency,
+    &'a HealthEfficiency,
+    &'a Generation,
+);
+
+type DefendingBug<'a> = (
+    Entity,
+    &'a Transform,
+    &'a mut Vitality,
     &'a Size,
+    &'a BaseDefence,
+    &'a Armour,
+    &'a Spines,
     &'a AgeEfficiency,
     &'a HealthEfficiency,
+    &'a Generation,
+    Option<&'a mut Poisoned>,
 );
 
+/// Damage to be dealt back to an attacker by the spines of the bug it hit.
+struct Reflected {
+    attacker: Entity,
+    damage: usize,
+    defender_generation: usize,
+}
+
+/// What one hit does to a defender, before rounding.
+#[derive(Debug, PartialEq)]
+struct HitDamage {
+    /// Taken from the defender's health straight away.
+    health: f32,
+    /// Dealt by venom over the poison duration.
+    poison: f32,
+    /// Dealt back to the attacker by the defender's spines.
+    reflected: f32,
+}
+
+/// Defence absorbs less of an attack from a larger bug, and armour takes its fraction off what
+/// gets through. Venom and spines deal their fractions of the damage that lands.
+fn hit_damage(
+    attack_strength: f32,
+    defence_absorption: f32,
+    attacker_size: f32,
+    defender_size: f32,
+    armour: f32,
+    venom: f32,
+    spines: f32,
+) -> HitDamage {
+    let size_multiplier = if attacker_size > defender_size {
+        defender_size / attacker_size
+    } else {
+        1.0
+    };
+    let health =
+        attack_strength * defence_absorption.mul_add(-size_multiplier, 1.0) * (1.0 - armour);
+    HitDamage {
+        health,
+        poison: health * venom,
+        reflected: health * spines,
+    }
+}
+
+/// Takes `damage` from the health of a bug, crediting `generation` with the kill if it dies.
+fn injure(
+    vitality: &mut Vitality,
+    damage: usize,
+    generation: usize,
+    source: &'static str,
+    combat_stats: &mut CombatStats,
+    ecosystem: &mut Ecosystem,
+    ledger: &mut EnergyLedger,
+) {
+    let was_alive = vitality.health().amount() > 0;
+    let lost_energy = vitality.health_mut().take_energy(damage);
+    ledger.record(EnergyFlow::Injured, source, lost_energy.amount());
+    ecosystem.return_energy(lost_energy);
+    if was_alive && vitality.health().amount() == 0 {
+        combat_stats.record_kill(generation);
+    }
+}
+
 fn attack_bug(
+    new_poison: &mut HashMap<Entity, Poisoned>,
     bug: &AttackingBug,
     other: &mut (
+        Entity,
         &Transform,
         Mut<Vitality>,
         &Size,
         &BaseDefence,
+        &Armour,
+        &Spines,
         &AgeEfficiency,
         &HealthEfficiency,
+        &Generation,
+        Option<Mut<Poisoned>>,
     ),
-    ecosystem: &mut ResMut<Ecosystem>,
-) {
-    let (bug_transform, mind_out, base_attack, size, age_efficiency, health_efficiency) = bug;
-    let (other_transform, vitality, other_size, base_defence, other_age, other_health) = other;
+    combat_stats: &mut CombatStats,
+    ecosystem: &mut Ecosystem,
+    ledger: &mut EnergyLedger,
+) -> Option<Reflected> {
+    let (
+        bug_entity,
+        bug_transform,
+        mind_out,
+        base_attack,
+        attack_cone,
+        venom,
+        size,
+        age_efficiency,
+        health_efficiency,
+        generation,
+    ) = bug;
+    let (
+        other_entity,
+        other_transform,
+        vitality,
+        other_size,
+        base_defence,
+        armour,
+        spines,
+        other_age,
+        other_health,
+        other_generation,
+        poisoned,
+    ) = other;
     let attack = mind_out[ATTACK_INDEX];
     if attack <= 0.0 {
-        return;
+        return None;
     }
 
     let translation_between = other_transform.translation - bug_transform.translation;
     let angle_to_other = angle_between(&bug_transform.rotation, translation_between);
 
-    if angle_to_other.abs() >= 0.2 {
-        return;
+    if angle_to_other.abs() >= ***attack_cone {
+        return None;
     }
 
     let attack_strength = ***base_attack * attack * ***age_efficiency * ***health_efficiency;
     let defence_absorption = ***base_defence * ***other_age * ***other_health;
 
-    let size_multiplier = if ***size > ***other_size {
-        ***other_size / ***size
-    } else {
-        1.0
-    };
-    let health_impact = attack_strength * defence_absorption.mul_add(-size_multiplier, 1.0);
-    let lost_energy = vitality
-        .health_mut()
-        .take_energy(health_impact.ceil() as usize);
-    ecosystem.return_energy(lost_energy);
+    let hit = hit_damage(
+        attack_strength,
+        defence_absorption,
+        ***size,
+        ***other_size,
+        ***armour,
+        ***venom,
+        ***spines,
+    );
+    injure(
+        vitality,
+        hit.health.ceil() as usize,
+        generation.0,
+        "attack_bug_system",
+        combat_stats,
+        ecosystem,
+        ledger,
+    );
+
+    if ***venom > 0.0 && vitality.health().amount() > 0 {
+        let duration = WorldConfig::global().poison_duration;
+        match poisoned {
+            Some(poisoned) => poisoned.add(hit.poison, duration, generation.0),
+            None => {
+                new_poison
+                    .entry(*other_entity)
+                    .and_modify(|poisoned| poisoned.add(hit.poison, duration, generation.0))
+                    .or_insert_with(|| Poisoned::new(hit.poison, duration, generation.0));
+            }
+        }
+    }
+
+    Some(Reflected {
+        attacker: *bug_entity,
+        damage: hit.reflected.round() as usize,
+        defender_generation: other_generation.0,
+    })
 }
 
 pub fn attack_egg_system(
@@ -86,30 +233,249 @@ pub fn attack_egg_system(
 }
 
 pub fn attack_bug_system(
+    mut commands: Commands,
     rapier_context: Res<RapierContext>,
+    mut combat_stats: ResMut<CombatStats>,
     mut ecosystem: ResMut<Ecosystem>,
+    mut ledger: ResMut<EnergyLedger>,
     bug_query: Query<AttackingBug>,
-    mut other_query: Query<(
-        &Transform,
-        &mut Vitality,
-        &Size,
-        &BaseDefence,
-        &AgeEfficiency,
-        &HealthEfficiency,
-    )>,
+    mut other_query: Query<DefendingBug>,
 ) {
-    for contact_pair in rapier_context.contact_pairs() {
-        if let (Ok(bug), Ok(mut other)) = (
-            bug_query.get(contact_pair.collider1()),
-            other_query.get_mut(contact_pair.collider2()),
-        ) {
-            attack_bug(&bug, &mut other, &mut ecosystem);
+    let contacts = rapier_context.contact_pairs().flat_map(|contact_pair| {
+        [
+            (contact_pair.collider1(), contact_pair.collider2()),
+            (contact_pair.collider2(), contact_pair.collider1()),
+        ]
+    });
+    attack_bugs(
+        contacts,
+        &mut commands,
+        &mut combat_stats,
+        &mut ecosystem,
+        &mut ledger,
+        &bug_query,
+        &mut other_query,
+    );
+}
+
+/// Lets every attacker hit the defender it touches, poisoning each defender once with the venom
+/// of all its hits.
+fn attack_bugs(
+    contacts: impl Iterator<Item = (Entity, Entity)>,
+    commands: &mut Commands,
+    combat_stats: &mut CombatStats,
+    ecosystem: &mut Ecosystem,
+    ledger: &mut EnergyLedger,
+    bug_query: &Query<AttackingBug>,
+    other_query: &mut Query<DefendingBug>,
+) {
+    let mut reflected = vec![];
+    let mut new_poison = HashMap::new();
+    for (attacker, defender) in contacts {
+        if let (Ok(bug), Ok(mut other)) = (bug_query.get(attacker), other_query.get_mut(defender)) {
+            reflected.extend(attack_bug(
+                &mut new_poison,
+                &bug,
+                &mut other,
+                combat_stats,
+                ecosystem,
+                ledger,
+            ));
+        }
+    }
+    for (defender, poisoned) in new_poison {
+        commands.entity(defender).insert(poisoned);
+    }
+
+    for hit in reflected.into_iter().filter(|hit| hit.damage > 0) {
+        if let Ok((_, _, mut vitality, ..)) = other_query.get_mut(hit.attacker) {
+            injure(
+                &mut vitality,
+                hit.damage,
+                hit.defender_generation,
+                "attack_bug_system",
+                combat_stats,
+                ecosystem,
+                ledger,
+            );
         }
-        if let (Ok(bug), Ok(mut other)) = (
-            bug_query.get(contact_pair.collider2()),
-            other_query.get_mut(contact_pair.collider1()),
-        ) {
-            attack_bug(&bug, &mut other, &mut ecosystem);
+    }
+}
+
+pub fn poison_system(
+    mut commands: Commands,
+    timesteps: Res<FixedTimesteps>,
+    mut combat_stats: ResMut<CombatStats>,
+    mut ecosystem: ResMut<Ecosystem>,
+    mut ledger: ResMut<EnergyLedger>,
+    mut poisoned_query: Query<(Entity, &mut Vitality, &mut Poisoned)>,
+) {
+    let standard = timesteps.get("standard").unwrap();
+    for (entity, mut vitality, mut poisoned) in poisoned_query.iter_mut() {
+        let damage = poisoned.tick(standard.step.as_secs_f32());
+        if damage > 0 {
+            injure(
+                &mut vitality,
+                damage,
+                poisoned.source_generation(),
+                "poison_system",
+                &mut combat_stats,
+                &mut ecosystem,
+                &mut ledger,
+            );
+        }
+        if poisoned.is_spent() {
+            commands.entity(entity).remove::<Poisoned>();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::{app::App, core::CorePlugin};
+    use genesis_attributes::{Dna, Genome};
+    use genesis_config as config;
+    use rand::{rngs::StdRng, SeedableRng};
+
+    use super::*;
+
+    fn close(a: f32, b: f32) -> bool {
+        (a - b).abs() < 1e-4
+    }
+
+    #[test]
+    fn armour_spines_and_venom_scale_the_damage_that_lands() {
+        let bare = hit_damage(100.0, 0.5, 20.0, 20.0, 0.0, 0.0, 0.0);
+        assert!(close(bare.health, 50.0));
+        assert_eq!(bare.poison, 0.0);
+        assert_eq!(bare.reflected, 0.0);
+
+        // Defence absorbs less of an attack from a bug twice the size.
+        let bigger = hit_damage(100.0, 0.5, 40.0, 20.0, 0.0, 0.0, 0.0);
+        assert!(close(bigger.health, 75.0));
+
+        let armed = hit_damage(100.0, 0.5, 20.0, 20.0, 0.2, 0.5, 0.1);
+        assert!(close(armed.health, 40.0));
+        assert!(close(armed.poison, 20.0));
+        assert!(close(armed.reflected, 4.0));
+    }
+
+    #[test]
+    fn kill_is_credited_only_on_the_killing_blow() {
+        config::initialize_configs(None);
+        let mut ecosystem = Ecosystem::new(10000);
+        let mut ledger = EnergyLedger::default();
+        let mut combat_stats = CombatStats::default();
+        let energy = ecosystem.request_energy(10000).unwrap();
+        let (mut vitality, leftover) = Vitality::new(&Size::new(20.0), energy);
+        ecosystem.return_energy(leftover);
+        let health = vitality.health().amount();
+        assert!(health > 1);
+
+        injure(
+            &mut vitality,
+            health - 1,
+            3,
+            "test",
+            &mut combat_stats,
+            &mut ecosystem,
+            &mut ledger,
+        );
+        assert!(combat_stats.kills_by_generation().is_empty());
+
+        for generation in [4, 5] {
+            injure(
+                &mut vitality,
+                health,
+                generation,
+                "test",
+                &mut combat_stats,
+                &mut ecosystem,
+                &mut ledger,
+            );
         }
+        assert_eq!(vitality.health().amount(), 0);
+        assert_eq!(combat_stats.kills_by_generation().get(&4), Some(&1));
+        assert!(!combat_stats.kills_by_generation().contains_key(&5));
+        assert_eq!(ledger.total(EnergyFlow::Injured), health);
+    }
+
+    #[test]
+    fn venom_from_every_hit_in_one_update_is_kept() {
+        config::initialize_configs(None);
+        let mut ecosystem = Ecosystem::new(100000);
+        let energy = ecosystem.request_energy(100000).unwrap();
+        let (vitality, leftover) = Vitality::new(&Size::new(20.0), energy);
+        ecosystem.return_energy(leftover);
+        let genome = Genome::new();
+        let base_defence =
+            BaseDefence::new(&Dna::new(&genome, &mut StdRng::seed_from_u64(2)), &genome);
+        let single_hit = hit_damage(20.0, *base_defence, 20.0, 20.0, 0.0, 0.5, 0.0);
+
+        let mut app = App::new();
+        app.add_plugin(CorePlugin::default())
+            .insert_resource(ecosystem)
+            .init_resource::<EnergyLedger>()
+            .init_resource::<CombatStats>();
+        let defender = app
+            .world
+            .spawn((
+                Transform::from_xyz(0.0, 10.0, 0.0),
+                vitality,
+                Size::new(20.0),
+                base_defence,
+                Armour::new(0.0),
+                Spines::new(0.0),
+                AgeEfficiency(1.0),
+                HealthEfficiency(1.0),
+                Generation(0),
+            ))
+            .id();
+        let attackers: Vec<Entity> = [3, 4]
+            .into_iter()
+            .map(|generation| {
+                app.world
+                    .spawn((
+                        Transform::default(),
+                        MindOutput(vec![1.0; config::OUTPUT_NEURONS]),
+                        BaseAttack::new(20.0),
+                        AttackCone::new(170.0),
+                        Venom::new(0.5),
+                        Size::new(20.0),
+                        AgeEfficiency(1.0),
+                        HealthEfficiency(1.0),
+                        Generation(generation),
+                    ))
+                    .id()
+            })
+            .collect();
+        app.add_system(
+            move |mut commands: Commands,
+                  mut combat_stats: ResMut<CombatStats>,
+                  mut ecosystem: ResMut<Ecosystem>,
+                  mut ledger: ResMut<EnergyLedger>,
+                  bug_query: Query<AttackingBug>,
+                  mut other_query: Query<DefendingBug>| {
+                attack_bugs(
+                    attackers.iter().map(|attacker| (*attacker, defender)),
+                    &mut commands,
+                    &mut combat_stats,
+                    &mut ecosystem,
+                    &mut ledger,
+                    &bug_query,
+                    &mut other_query,
+                );
+            },
+        );
+
+        app.update();
+
+        let duration = WorldConfig::global().poison_duration;
+        let mut poisoned = app.world.get_mut::<Poisoned>(defender).unwrap();
+        assert_eq!(poisoned.source_generation(), 4);
+        assert_eq!(
+            poisoned.tick(duration * 2.0),
+            (single_hit.poison * 2.0).floor() as usize
+        );
     }
 }
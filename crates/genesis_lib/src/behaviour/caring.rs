@@ -0,0 +1,423 @@
+use std::time::Duration;
+
+use bevy::{
+    prelude::{Entity, Query, Res, ResMut, Transform, Vec3, With, Without},
+    utils::HashMap,
+};
+use genesis_attributes::HatchSize;
+use genesis_components::{
+    body::Vitality, mind::MindOutput, time::Age, Adult, Egg, Relations, Size,
+};
+use genesis_config::{ParentalCareConfig, WorldConfig, FEED_INDEX};
+use genesis_ecosystem::{Ecosystem, EggEnergy, Energy};
+use iyes_loopless::prelude::FixedTimesteps;
+
+use crate::auditor::{EnergyFlow, EnergyLedger};
+
+type Parent<'a> = (
+    Entity,
+    &'a Relations,
+    &'a Transform,
+    &'a MindOutput,
+    &'a mut Vitality,
+);
+
+type Offspring<'a> = (
+    Entity,
+    &'a Relations,
+    &'a Transform,
+    Option<&'a mut Vitality>,
+    Option<&'a mut EggEnergy>,
+    Option<&'a HatchSize>,
+);
+
+type GuardingParent<'a> = (&'a Relations, &'a Transform);
+
+type GuardedEgg<'a> = (&'a Relations, &'a Transform, &'a mut Age);
+
+/// Ages eggs faster while their parent is close by.
+pub fn guard_egg_system(
+    timesteps: Res<FixedTimesteps>,
+    parent_query: Query<GuardingParent, With<Adult>>,
+    mut egg_query: Query<GuardedEgg, With<Egg>>,
+) {
+    let standard = timesteps.get("standard").unwrap();
+    guard_eggs(
+        &WorldConfig::global().parental_care,
+        standard.step,
+        &parent_query,
+        &mut egg_query,
+    );
+}
+
+fn guard_eggs(
+    care: &ParentalCareConfig,
+    step: Duration,
+    parent_query: &Query<GuardingParent, With<Adult>>,
+    egg_query: &mut Query<GuardedEgg, With<Egg>>,
+) {
+    if !care.enabled {
+        return;
+    }
+    let extra_age = step.mul_f32(care.guarded_hatch_speed - 1.0);
+    let parents: HashMap<u32, Vec3> = parent_query
+        .iter()
+        .map(|(relations, transform)| (relations.entity().0, transform.translation))
+        .collect();
+
+    for (relations, transform, mut age) in egg_query.iter_mut() {
+        let guarded = relations
+            .parent()
+            .and_then(|parent| parents.get(&parent))
+            .is_some_and(|position| position.distance(transform.translation) <= care.care_range);
+        if guarded {
+            age.tick(extra_age);
+        }
+    }
+}
+
+/// Moves up to `amount` of a parent's stored energy, never its health, to one of its offspring.
+///
+/// An egg is given no more than `capacity`, the energy it can hatch with. Returns the amount fed
+/// and any energy that fitted neither the offspring nor back in the parent.
+fn feed(
+    parent: &mut Vitality,
+    vitality: Option<&mut Vitality>,
+    egg: Option<(&mut EggEnergy, usize)>,
+    amount: usize,
+) -> (usize, Energy) {
+    let amount = amount.min(parent.energy_store().amount());
+    match (vitality, egg) {
+        (Some(vitality), _) => {
+            let amount = amount.min(vitality.available_space());
+            let leftover = vitality.add_energy(parent.take_energy(amount));
+            let fed = amount - leftover.amount();
+            (fed, parent.add_energy(leftover))
+        }
+        (None, Some((egg_energy, capacity))) => {
+            let amount = amount.min(capacity.saturating_sub(egg_energy.0.amount()));
+            egg_energy.0.add_energy(parent.take_energy(amount));
+            (amount, Energy::new_empty())
+        }
+        (None, None) => (0, Energy::new_empty()),
+    }
+}
+
+/// Passes energy from parents using their feed output to their nearest egg or juvenile.
+pub fn feed_offspring_system(
+    timesteps: Res<FixedTimesteps>,
+    mut ecosystem: ResMut<Ecosystem>,
+    mut ledger: ResMut<EnergyLedger>,
+    mut parent_query: Query<Parent, With<Adult>>,
+    mut offspring_query: Query<Offspring, Without<Adult>>,
+) {
+    let standard = timesteps.get("standard").unwrap();
+    feed_offspring(
+        &WorldConfig::global().parental_care,
+        standard.step,
+        &mut ecosystem,
+        &mut ledger,
+        &mut parent_query,
+        &mut offspring_query,
+    );
+}
+
+fn feed_offspring(
+    care: &ParentalCareConfig,
+    step: Duration,
+    ecosystem: &mut Ecosystem,
+    ledger: &mut EnergyLedger,
+    parent_query: &mut Query<Parent, With<Adult>>,
+    offspring_query: &mut Query<Offspring, Without<Adult>>,
+) {
+    if !care.enabled {
+        return;
+    }
+    let feed_amount = (care.feed_rate * step.as_secs_f32()) as usize;
+    let feeders: HashMap<u32, (Entity, Vec3)> = parent_query
+        .iter()
+        .filter(|(_, _, _, mind_out, _)| mind_out[FEED_INDEX] > 0.0)
+        .map(|(entity, relations, transform, ..)| {
+            (relations.entity().0, (entity, transform.translation))
+        })
+        .collect();
+
+    let mut nearest: HashMap<Entity, (Entity, f32)> = HashMap::new();
+    for (child, relations, transform, ..) in offspring_query.iter() {
+        let Some((parent, position)) = relations.parent().and_then(|id| feeders.get(&id)) else {
+            continue;
+        };
+        let distance = position.distance(transform.translation);
+        if distance > care.care_range {
+            continue;
+        }
+        let closest = nearest.entry(*parent).or_insert((child, distance));
+        if distance < closest.1 {
+            *closest = (child, distance);
+        }
+    }
+
+    for (parent, (child, _)) in nearest {
+        let (Ok((.., mut parent_vitality)), Ok((.., mut vitality, mut egg_energy, hatch_size))) =
+            (parent_query.get_mut(parent), offspring_query.get_mut(child))
+        else {
+            continue;
+        };
+        let egg = egg_energy
+            .as_deref_mut()
+            .zip(hatch_size.map(|hatch_size| Vitality::capacity(&Size::new(**hatch_size))));
+        let (fed, leftover) = feed(
+            &mut parent_vitality,
+            vitality.as_deref_mut(),
+            egg,
+            feed_amount,
+        );
+        ledger.record(EnergyFlow::Fed, "feed_offspring_system", fed);
+        ledger.record(
+            EnergyFlow::Burnt,
+            "feed_offspring_system",
+            leftover.amount(),
+        );
+        ecosystem.return_energy(leftover);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::{app::App, core::CorePlugin, prelude::Entity, render::color::Color};
+    use genesis_attributes::{Dna, Genome};
+    use genesis_config as config;
+    use rand::{rngs::StdRng, SeedableRng};
+
+    use super::*;
+
+    const STEP: Duration = Duration::from_secs(1);
+
+    fn care() -> ParentalCareConfig {
+        ParentalCareConfig {
+            enabled: true,
+            care_range: 50.0,
+            guarded_hatch_speed: 2.0,
+            feed_rate: 30.0,
+        }
+    }
+
+    fn vitality(ecosystem: &mut Ecosystem, energy: usize) -> Vitality {
+        let energy = ecosystem.request_energy(energy).unwrap();
+        let (vitality, leftover) = Vitality::new(&Size::new(20.0), energy);
+        ecosystem.return_energy(leftover);
+        vitality
+    }
+
+    fn relations(entity: Entity, parent: Option<Entity>) -> Relations {
+        Relations::new((entity, Color::WHITE), parent)
+    }
+
+    fn spawn_parent(app: &mut App, vitality: Vitality) -> Entity {
+        let parent = app.world.spawn_empty().id();
+        let mut mind_out = MindOutput(vec![0.0; config::OUTPUT_NEURONS]);
+        mind_out[FEED_INDEX] = 1.0;
+        app.world.entity_mut(parent).insert((
+            Adult,
+            relations(parent, None),
+            Transform::default(),
+            mind_out,
+            vitality,
+        ));
+        parent
+    }
+
+    fn spawn_egg(app: &mut App, parent: Entity, x: f32, energy: EggEnergy) -> Entity {
+        let egg = app.world.spawn_empty().id();
+        app.world.entity_mut(egg).insert((
+            Egg,
+            relations(egg, Some(parent)),
+            Transform::from_xyz(x, 0.0, 0.0),
+            Age::default(),
+            energy,
+            hatch_size(),
+        ));
+        egg
+    }
+
+    fn hatch_size() -> HatchSize {
+        let genome = Genome::new();
+        HatchSize::new(&Dna::new(&genome, &mut StdRng::seed_from_u64(2)), &genome)
+    }
+
+    #[test]
+    fn feeding_is_capped_by_the_parents_store() {
+        config::initialize_configs(None);
+        let mut ecosystem = Ecosystem::new(100000);
+        let mut parent = vitality(&mut ecosystem, 10000);
+        let mut egg_energy = EggEnergy(ecosystem.request_energy(10).unwrap());
+        let stored = parent.energy_store().amount();
+        let health = parent.health().amount();
+        let total = parent.total_energy() + egg_energy.0.amount();
+
+        let (fed, leftover) = feed(
+            &mut parent,
+            None,
+            Some((&mut egg_energy, usize::MAX)),
+            usize::MAX,
+        );
+
+        assert_eq!(fed, stored);
+        assert_eq!(leftover.amount(), 0);
+        assert_eq!(parent.energy_store().amount(), 0);
+        assert_eq!(parent.health().amount(), health);
+        assert_eq!(egg_energy.0.amount(), 10 + stored);
+        assert_eq!(parent.total_energy() + egg_energy.0.amount(), total);
+    }
+
+    #[test]
+    fn feeding_is_capped_by_the_juveniles_space() {
+        config::initialize_configs(None);
+        let mut ecosystem = Ecosystem::new(1000000);
+        let mut parent = vitality(&mut ecosystem, 10000);
+        let mut juvenile = vitality(&mut ecosystem, 100000);
+        assert_eq!(juvenile.available_space(), 0);
+        ecosystem.return_energy(juvenile.take_energy(5));
+        let mut egg_energy = EggEnergy(ecosystem.request_energy(10).unwrap());
+        let total = parent.total_energy() + juvenile.total_energy() + egg_energy.0.amount();
+
+        let (fed, leftover) = feed(
+            &mut parent,
+            Some(&mut juvenile),
+            Some((&mut egg_energy, usize::MAX)),
+            20,
+        );
+
+        assert_eq!(fed, 5);
+        assert_eq!(leftover.amount(), 0);
+        assert_eq!(juvenile.available_space(), 0);
+        assert_eq!(egg_energy.0.amount(), 10);
+        assert_eq!(
+            parent.total_energy() + juvenile.total_energy() + egg_energy.0.amount(),
+            total
+        );
+        assert_eq!(feed(&mut parent, None, None, 20).0, 0);
+    }
+
+    #[test]
+    fn eggs_are_fed_no_more_than_they_can_hatch_with() {
+        config::initialize_configs(None);
+        let mut ecosystem = Ecosystem::new(100000);
+        let mut parent = vitality(&mut ecosystem, 10000);
+        let mut egg_energy = EggEnergy(ecosystem.request_energy(10).unwrap());
+
+        assert_eq!(
+            feed(&mut parent, None, Some((&mut egg_energy, 15)), 20).0,
+            5
+        );
+        assert_eq!(egg_energy.0.amount(), 15);
+        assert_eq!(
+            feed(&mut parent, None, Some((&mut egg_energy, 15)), 20).0,
+            0
+        );
+        assert_eq!(feed(&mut parent, None, Some((&mut egg_energy, 5)), 20).0, 0);
+        assert_eq!(egg_energy.0.amount(), 15);
+    }
+
+    #[test]
+    fn parents_feed_only_their_nearest_offspring_in_range() {
+        config::initialize_configs(None);
+        let mut ecosystem = Ecosystem::new(100000);
+        let mut app = App::new();
+        app.add_plugin(CorePlugin::default())
+            .insert_resource(Ecosystem::new(0))
+            .init_resource::<EnergyLedger>()
+            .add_system(
+                |mut ecosystem: ResMut<Ecosystem>,
+                 mut ledger: ResMut<EnergyLedger>,
+                 mut parent_query: Query<Parent, With<Adult>>,
+                 mut offspring_query: Query<Offspring, Without<Adult>>| {
+                    feed_offspring(
+                        &care(),
+                        STEP,
+                        &mut ecosystem,
+                        &mut ledger,
+                        &mut parent_query,
+                        &mut offspring_query,
+                    );
+                },
+            );
+        let parent = spawn_parent(&mut app, vitality(&mut ecosystem, 10000));
+        let far = spawn_egg(&mut app, parent, 40.0, EggEnergy::default());
+        let near = spawn_egg(&mut app, parent, 20.0, EggEnergy::default());
+        let out_of_range = spawn_egg(&mut app, parent, -60.0, EggEnergy::default());
+
+        app.update();
+
+        let egg_energy = |egg: Entity| app.world.get::<EggEnergy>(egg).unwrap().0.amount();
+        assert_eq!(egg_energy(near), 30);
+        assert_eq!(egg_energy(far), 0);
+        assert_eq!(egg_energy(out_of_range), 0);
+        assert_eq!(
+            app.world.resource::<EnergyLedger>().total(EnergyFlow::Fed),
+            30
+        );
+    }
+
+    #[test]
+    fn parents_stop_feeding_eggs_at_their_hatch_energy() {
+        config::initialize_configs(None);
+        let mut ecosystem = Ecosystem::new(100000);
+        let mut app = App::new();
+        app.add_plugin(CorePlugin::default())
+            .insert_resource(Ecosystem::new(0))
+            .init_resource::<EnergyLedger>()
+            .add_system(
+                |mut ecosystem: ResMut<Ecosystem>,
+                 mut ledger: ResMut<EnergyLedger>,
+                 mut parent_query: Query<Parent, With<Adult>>,
+                 mut offspring_query: Query<Offspring, Without<Adult>>| {
+                    feed_offspring(
+                        &care(),
+                        STEP,
+                        &mut ecosystem,
+                        &mut ledger,
+                        &mut parent_query,
+                        &mut offspring_query,
+                    );
+                },
+            );
+        let parent = spawn_parent(&mut app, vitality(&mut ecosystem, 10000));
+        let capacity = Vitality::capacity(&Size::new(*hatch_size()));
+        let nearly_full = EggEnergy(ecosystem.request_energy(capacity - 10).unwrap());
+        let egg = spawn_egg(&mut app, parent, 20.0, nearly_full);
+
+        app.update();
+        app.update();
+
+        assert_eq!(
+            app.world.get::<EggEnergy>(egg).unwrap().0.amount(),
+            capacity
+        );
+        let ledger = app.world.resource::<EnergyLedger>();
+        assert_eq!(ledger.total(EnergyFlow::Fed), 10);
+        assert_eq!(ledger.total(EnergyFlow::Burnt), 0);
+    }
+
+    #[test]
+    fn parents_guard_only_eggs_in_range() {
+        config::initialize_configs(None);
+        let mut ecosystem = Ecosystem::new(100000);
+        let mut app = App::new();
+        app.add_plugin(CorePlugin::default()).add_system(
+            |parent_query: Query<GuardingParent, With<Adult>>,
+             mut egg_query: Query<GuardedEgg, With<Egg>>| {
+                guard_eggs(&care(), STEP, &parent_query, &mut egg_query);
+            },
+        );
+        let parent = spawn_parent(&mut app, vitality(&mut ecosystem, 10000));
+        let guarded = spawn_egg(&mut app, parent, 50.0, EggEnergy::default());
+        let unguarded = spawn_egg(&mut app, parent, 51.0, EggEnergy::default());
+
+        app.update();
+
+        let age = |egg: Entity| app.world.get::<Age>(egg).unwrap().elapsed_secs();
+        assert_eq!(age(guarded), 1.0);
+        assert_eq!(age(unguarded), 0.0);
+    }
+}
@@ -1,16 +1,24 @@
 use bevy::{
-    prelude::{Commands, Entity, EventWriter, Mut, Query, Res, Transform, With, Without},
+    prelude::{Commands, Entity, EventWriter, Mut, Query, Res, ResMut, Transform, With, Without},
     time::Stopwatch,
 };
 use bevy_rapier2d::prelude::RapierContext;
 use genesis_attributes as attributes;
-use genesis_components::{body::Vitality, eat::*, mind::MindOutput, BurntEnergy, Egg, Size};
+use genesis_components::{
+    body::Vitality, eat::*, mind::MindOutput, BurntEnergy, Egg, Grazed, Lineage, MeatSource, Plant,
+    Relations, Size,
+};
 use genesis_config as config;
 use genesis_ecosystem::Food;
 use genesis_maths::angle_between;
 use genesis_traits::BehaviourTracker;
 use iyes_loopless::prelude::FixedTimesteps;
 
+use crate::{
+    auditor::{EnergyFlow, EnergyLedger},
+    statistics::{FoodWeb, Meal},
+};
+
 pub fn process_eaters_system(
     mut commands: Commands,
     not_eating_query: Query<(Entity, &MindOutput), (Without<Egg>, Without<TryingToEat>)>,
@@ -49,28 +57,56 @@ pub fn attempted_to_eat_system(
 fn eat_food(
     commands: &mut Commands,
     ev_eaten: &mut EventWriter<EatenEvent>,
+    ledger: &mut EnergyLedger,
+    food_web: &mut FoodWeb,
     bug: &mut (
         Mut<Stomach>,
         &Transform,
         &Size,
         Mut<EnergyConsumed>,
         &attributes::MouthWidth,
+        &Lineage,
+        &Relations,
+    ),
+    food: &mut (
+        Entity,
+        Mut<Food>,
+        &Transform,
+        Option<&Plant>,
+        Option<&MeatSource>,
     ),
-    food: &mut (Entity, Mut<Food>, &Transform),
 ) {
-    let (stomach, bug_transform, bug_size, energy_consumed, mouth_width) = bug;
-    let (food_entity, food_energy, food_transform) = food;
+    let (stomach, bug_transform, bug_size, energy_consumed, mouth_width, lineage, relations) = bug;
+    let (food_entity, food_energy, food_transform, plant, meat_source) = food;
     let angle_to_food = angle_between(
         &bug_transform.rotation,
         food_transform.translation - bug_transform.translation,
     );
     if angle_to_food.abs() < ***mouth_width {
         let initial_food_energy = food_energy.energy().amount();
-        stomach.eat(food_energy, bug_size);
+        if plant.is_some() {
+            stomach.eat_plant(food_energy, bug_size);
+        } else {
+            stomach.eat_meat(food_energy, bug_size);
+        }
         let consumed = initial_food_energy - food_energy.energy().amount();
         energy_consumed.0 += consumed;
+        let food_kind = if plant.is_some() { "plant" } else { "meat" };
+        ledger.record_part(EnergyFlow::Eaten, "eating_system", food_kind, consumed);
+        let meal = if plant.is_some() {
+            Meal::Plant
+        } else {
+            Meal::Meat {
+                lineage: meat_source.map(|source| *source.lineage()),
+                kin: meat_source.is_some_and(|source| source.is_kin_of(relations)),
+            }
+        };
+        food_web.record(**lineage, meal, consumed);
         if consumed > 0 {
             ev_eaten.send(EatenEvent(*food_entity));
+            if plant.is_some() {
+                commands.entity(*food_entity).insert(Grazed);
+            }
         }
         if food_energy.energy().amount() == 0 {
             commands.entity(*food_entity).insert(Eaten);
@@ -84,13 +120,23 @@ pub type EatingBug<'a> = (
     &'a Size,
     &'a mut EnergyConsumed,
     &'a attributes::MouthWidth,
+    &'a Lineage,
+    &'a Relations,
 );
 
-pub type EatenFood<'a> = (Entity, &'a mut Food, &'a Transform);
+pub type EatenFood<'a> = (
+    Entity,
+    &'a mut Food,
+    &'a Transform,
+    Option<&'a Plant>,
+    Option<&'a MeatSource>,
+);
 
 pub fn eating_system(
     mut commands: Commands,
     mut ev_eaten: EventWriter<EatenEvent>,
+    mut ledger: ResMut<EnergyLedger>,
+    mut food_web: ResMut<FoodWeb>,
     rapier_context: Res<RapierContext>,
     mut bug_query: Query<EatingBug, With<TryingToEat>>,
     mut food_query: Query<EatenFood>,
@@ -105,7 +151,14 @@ pub fn eating_system(
         };
 
         if let Ok(mut food) = food_query.get_mut(other_collider) {
-            eat_food(&mut commands, &mut ev_eaten, &mut bug, &mut food);
+            eat_food(
+                &mut commands,
+                &mut ev_eaten,
+                &mut ledger,
+                &mut food_web,
+                &mut bug,
+                &mut food,
+            );
         }
     }
 }
@@ -117,6 +170,7 @@ pub fn digestion_intensity_system(mut bug_query: Query<(&MindOutput, &mut Stomac
 }
 
 pub fn digest_food_system(
+    mut ledger: ResMut<EnergyLedger>,
     mut bug_query: Query<(
         &attributes::FoodPreference,
         &mut Stomach,
@@ -139,7 +193,13 @@ pub fn digest_food_system(
         let usable_energy_amount = usable_energy.amount();
         let more_waste_energy = vitality.add_energy(usable_energy);
         energy_used.0 = usable_energy_amount - more_waste_energy.amount();
+        ledger.record(EnergyFlow::Digested, "digest_food_system", energy_used.0);
         waste_energy.add_energy(more_waste_energy);
+        ledger.record(
+            EnergyFlow::Wasted,
+            "digest_food_system",
+            waste_energy.amount(),
+        );
         energy_wasted.0 = stomach.digestion_cost();
         burnt_energy.add_energy(waste_energy);
     }
@@ -53,6 +53,7 @@ pub fn grow_bug_system(
     mut grower_query: Query<
         (
             &attributes::MaxSize,
+            &attributes::AspectRatio,
             &mut body::Vitality,
             &mut Size,
             &mut Sprite,
@@ -66,6 +67,7 @@ pub fn grow_bug_system(
 ) {
     for (
         max_size,
+        aspect_ratio,
         mut vitality,
         mut size,
         mut sprite,
@@ -84,21 +86,81 @@ pub fn grow_bug_system(
         }
         size.grow(grow_amount as f32);
         vitality.grow(grow_amount, size.as_uint());
-        sprite.custom_size = Some(spawning::bug_sprite_size(&size));
-        *collider = spawning::bug_collider(&size);
+        sprite.custom_size = Some(spawning::bug_sprite_size(&size, aspect_ratio));
+        *collider = spawning::bug_collider(&size, aspect_ratio);
         size_multiplier.update(**size);
         stomach.update_capacity(**size);
     }
 }
 
+/// The upkeep of each unit of size. Density, speed and turning are neutral at 1 and only cost
+/// above it.
+fn unit_upkeep(
+    world_config: &config::WorldConfig,
+    body_density: f32,
+    top_speed: f32,
+    turn_rate: f32,
+    venom: f32,
+    spines: f32,
+) -> f32 {
+    let above_neutral = |gene: f32| (gene - 1.0).max(0.0);
+    let body_plan_cost = world_config.turn_rate_cost.mul_add(
+        above_neutral(turn_rate),
+        world_config.top_speed_cost.mul_add(
+            above_neutral(top_speed),
+            world_config
+                .density_cost
+                .mul_add(above_neutral(body_density), world_config.unit_size_cost),
+        ),
+    );
+    world_config.spines_cost.mul_add(
+        spines,
+        world_config.venom_cost.mul_add(venom, body_plan_cost),
+    )
+}
+
 pub fn existence_system(
     timesteps: Res<FixedTimesteps>,
-    mut bug_query: Query<(&Size, &mut SizeSum)>,
+    mut bug_query: Query<(
+        &Size,
+        &attributes::BodyDensity,
+        &attributes::TopSpeed,
+        &attributes::TurnRate,
+        &attributes::Venom,
+        &attributes::Spines,
+        &mut SizeSum,
+    )>,
 ) {
-    let unit_size_cost = config::WorldConfig::global().unit_size_cost;
+    let world_config = config::WorldConfig::global();
     let standard = timesteps.get("standard").unwrap();
 
-    for (size, mut size_sum) in bug_query.iter_mut() {
-        size_sum.add_time(standard.step.as_secs_f32(), **size * unit_size_cost);
+    for (size, body_density, top_speed, turn_rate, venom, spines, mut size_sum) in
+        bug_query.iter_mut()
+    {
+        let unit_cost = unit_upkeep(
+            world_config,
+            **body_density,
+            **top_speed,
+            **turn_rate,
+            **venom,
+            **spines,
+        );
+        size_sum.add_time(standard.step.as_secs_f32(), **size * unit_cost);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn neutral_body_plan_costs_only_its_size() {
+        let world_config = config::WorldConfig::default();
+        let base = world_config.unit_size_cost;
+
+        assert_eq!(unit_upkeep(&world_config, 1.0, 1.0, 1.0, 0.0, 0.0), base);
+        assert_eq!(unit_upkeep(&world_config, 0.5, 0.8, 0.8, 0.0, 0.0), base);
+        let dense = unit_upkeep(&world_config, 1.5, 1.0, 1.0, 0.0, 0.0);
+        assert!((dense - world_config.density_cost.mul_add(0.5, base)).abs() < 1e-6);
     }
 }
@@ -12,7 +12,10 @@ use genesis_spawners::Spawners;
 use genesis_traits::BehaviourTracker;
 use iyes_loopless::prelude::FixedTimesteps;
 
-use crate::{spawning, statistics};
+use crate::{
+    auditor::{EnergyFlow, EnergyLedger},
+    spawning, statistics,
+};
 
 type LayerTest<'a> = (Entity, &'a mind::MindOutput);
 
@@ -62,6 +65,7 @@ type Parent<'a> = (
     &'a components::Generation,
     &'a mut EggsLaid,
     &'a mut components::Relations,
+    &'a components::Lineage,
     &'a attributes::Dna,
 );
 
@@ -70,9 +74,11 @@ pub fn lay_egg_system(
     asset_server: Res<AssetServer>,
     genome: Res<attributes::Genome>,
     mind_thresholds: Res<mind::MindThresholds>,
+    mut ledger: ResMut<EnergyLedger>,
     mut parent_query: Query<Parent, With<TryingToLay>>,
 ) {
-    let prob = Probability::new(config::WorldConfig::global().mutation_probability).unwrap();
+    let global_rate = config::WorldConfig::global().mutation_probability;
+    let global_mean = *mind_thresholds.mean_mutations();
     let mut rng = rand::thread_rng();
     for (
         entity,
@@ -83,6 +89,7 @@ pub fn lay_egg_system(
         generation,
         mut eggs_laid,
         mut relations,
+        lineage,
         dna,
     ) in parent_query.iter_mut()
     {
@@ -92,8 +99,14 @@ pub fn lay_egg_system(
             continue;
         }
         let energy = vitality.take_energy(egg_energy);
+        ledger.record(EnergyFlow::Laid, "lay_egg_system", energy.amount());
         let location = egg_position(transform);
         eggs_laid.0 += 1;
+        let (prob, mean_mutations) = mutation_rates(dna.mutation_rate, global_rate, global_mean);
+        let child_mind: mind::Mind = mind
+            .mutate(&mut rng, mean_mutations, &mind_thresholds)
+            .into();
+        let mutations = mind::MindMutations::from_parent(mind, &child_mind);
         let egg_entity = spawning::spawn_egg(
             &mut commands,
             &asset_server,
@@ -101,14 +114,33 @@ pub fn lay_egg_system(
             energy,
             location,
             genome.mutate(*dna, &mut rng, &prob),
-            mind.mutate(&mut rng, &prob, &mind_thresholds).into(),
+            child_mind,
             *generation + 1.into(),
-            Some(entity),
+            Some((entity, *lineage)),
         );
+        commands.entity(egg_entity).insert(mutations);
         relations.add_child(egg_entity);
     }
 }
 
+/// The chance of each gene mutating and the mean number of brain mutations in a child.
+///
+/// A heritable mutation rate replaces the world's gene mutation probability, and scales the
+/// world's mean brain mutations by the same factor.
+fn mutation_rates(rate: Option<f32>, global_rate: f32, global_mean: f32) -> (Probability, f32) {
+    let global_rate = global_rate.clamp(0.0, 1.0);
+    let Some(rate) = rate else {
+        return (Probability::new(global_rate).unwrap(), global_mean);
+    };
+    let rate = rate.clamp(0.0, 1.0);
+    let mean_mutations = if global_rate > 0.0 {
+        global_mean * rate / global_rate
+    } else {
+        global_mean
+    };
+    (Probability::new(rate).unwrap(), mean_mutations)
+}
+
 fn egg_position(parent_transform: &Transform) -> Vec3 {
     let separation = 20.0;
     let mut egg_pos = parent_transform.translation;
@@ -126,6 +158,7 @@ pub fn spawn_egg_system(
     asset_server: Res<AssetServer>,
     genome: Res<attributes::Genome>,
     mut ecosystem: ResMut<ecosystem::Ecosystem>,
+    mut ledger: ResMut<EnergyLedger>,
     spawners: Res<Spawners>,
     count_stats: Res<statistics::CountStats>,
     performance_stats: Res<statistics::BugPerformance>,
@@ -138,20 +171,19 @@ pub fn spawn_egg_system(
     if (spawners.space_for_organisms(config_instance.minimum_number))
         || (bug_num < config_instance.start_num && max_generation < config::GENERATION_SWITCH)
     {
-        let Some(energy) = ecosystem.request_energy(config_instance.start_energy) else { return };
         let mut rng = rand::thread_rng();
-        let location = spawners.random_organism_position(&mut rng);
+        let Some(location) = spawners.random_organism_position(&mut rng) else { return };
+        let Some(energy) = ecosystem.request_energy(config_instance.start_energy) else { return };
+        ledger.record(EnergyFlow::Spawned, "spawn_egg_system", energy.amount());
         let dna = attributes::Dna::new(&genome, &mut rng);
-        let mut mind = mind::Mind::minimal(
+        let mind = mind::Mind::minimal(
             config::INPUT_NEURONS,
             config::OUTPUT_NEURONS,
             &config_instance.starting_synapses,
         );
-        for _ in 0..config_instance.mutations {
-            mind = mind
-                .mutate(&mut rng, &Probability::new(1.0).unwrap(), &mind_thresholds)
-                .into();
-        }
+        let mind = mind
+            .mutate_times(&mut rng, config_instance.mutations, &mind_thresholds)
+            .into();
         spawning::spawn_egg(
             &mut commands,
             &asset_server,
@@ -165,3 +197,26 @@ pub fn spawn_egg_system(
         );
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn mutation_rate_scales_brain_mutations() {
+        let (prob, mean) = mutation_rates(None, 0.1, 0.5);
+        assert_eq!(prob.as_float(), 0.1);
+        assert_eq!(mean, 0.5);
+
+        let (prob, mean) = mutation_rates(Some(0.2), 0.1, 0.5);
+        assert_eq!(prob.as_float(), 0.2);
+        assert!((mean - 1.0).abs() < f32::EPSILON);
+
+        let (prob, mean) = mutation_rates(Some(1.0001), 0.1, 0.5);
+        assert_eq!(prob.as_float(), 1.0);
+        assert!((mean - 5.0).abs() < 1e-5);
+
+        let (_, mean) = mutation_rates(Some(0.2), 0.0, 0.5);
+        assert_eq!(mean, 0.5);
+    }
+}
@@ -3,8 +3,11 @@ use genesis_components::{body, BurntEnergy};
 use genesis_ecosystem as ecosystem;
 use genesis_traits::BehaviourTracker;
 
+use crate::auditor::{EnergyFlow, EnergyLedger};
+
 pub fn energy_return_system(
     mut ecosystem: ResMut<ecosystem::Ecosystem>,
+    mut ledger: ResMut<EnergyLedger>,
     mut query: Query<(
         &mut body::Vitality,
         &mut dyn BehaviourTracker,
@@ -15,11 +18,24 @@ pub fn energy_return_system(
         for mut tracker in trackers.iter_mut() {
             let cost = tracker.uint_portion();
             if cost >= 1 {
-                burnt_energy.add_energy(vitality.take_energy(cost));
+                let spent_energy = vitality.take_energy(cost);
+                ledger.record_part(
+                    EnergyFlow::Spent,
+                    "energy_return_system",
+                    tracker.name(),
+                    spent_energy.amount(),
+                );
+                burnt_energy.add_energy(spent_energy);
             }
         }
 
-        ecosystem.return_energy(burnt_energy.return_energy());
+        let returned_energy = burnt_energy.return_energy();
+        ledger.record(
+            EnergyFlow::Burnt,
+            "energy_return_system",
+            returned_energy.amount(),
+        );
+        ecosystem.return_energy(returned_energy);
     }
 }
 
@@ -6,6 +6,7 @@ use iyes_loopless::prelude::*;
 use crate::{conditions, SimState};
 
 pub mod attacking;
+pub mod caring;
 pub mod eating;
 pub mod grabbing;
 pub mod growing;
@@ -76,6 +77,9 @@ pub fn other_behaviour_system_set() -> SystemSet {
         .with_system(grabbing::grabbing_system)
         .with_system(attacking::attack_bug_system)
         .with_system(attacking::attack_egg_system)
+        .with_system(attacking::poison_system)
+        .with_system(caring::guard_egg_system)
+        .with_system(caring::feed_offspring_system)
         .into()
 }
 
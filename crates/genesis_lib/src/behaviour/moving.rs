@@ -1,5 +1,6 @@
 use bevy::prelude::{Query, Res, Transform};
 use bevy_rapier2d::prelude::Velocity;
+use genesis_attributes::{Armour, TopSpeed, TurnRate};
 use genesis_components::{
     body::HealthEfficiency, mind, time::AgeEfficiency, RotationSum, SizeMultiplier, TranslationSum,
 };
@@ -13,6 +14,9 @@ pub fn movement_system(
         &Transform,
         &mut Velocity,
         &mind::MindOutput,
+        &TopSpeed,
+        &TurnRate,
+        &Armour,
         &mut TranslationSum,
         &mut RotationSum,
         &SizeMultiplier,
@@ -27,6 +31,9 @@ pub fn movement_system(
         transform,
         mut velocity,
         outputs,
+        top_speed,
+        turn_rate,
+        armour,
         mut translation_sum,
         mut rotation_sum,
         size_multiplier,
@@ -42,6 +49,7 @@ pub fn movement_system(
         velocity.angvel = size_multiplier.as_float()
             * rotation_factor
             * world_config.max_rotation
+            * **turn_rate
             * **health_efficiency
             * **age_efficiency;
 
@@ -53,6 +61,8 @@ pub fn movement_system(
         let speed = size_multiplier.as_float()
             * movement_factor
             * world_config.max_translation
+            * **top_speed
+            * armour.mul_add(-world_config.armour_speed_penalty, 1.0)
             * **health_efficiency
             * **age_efficiency;
         velocity.linvel = (speed * transform.local_y()).truncate();
@@ -4,9 +4,14 @@ use bevy::{
 };
 use bevy_rapier2d::prelude::{QueryFilter, RapierContext};
 use genesis_attributes::{EyeAngle, EyeRange};
-use genesis_components::{mind::Mind, see::Vision, time::AgeEfficiency, Meat, Plant};
+use genesis_components::{
+    mind::Mind, see::Vision, time::AgeEfficiency, Meat, Plant, PlantType, Relations,
+};
+use genesis_config::WorldConfig;
 use genesis_maths::{angle_between, cast_angles, point_from_angle, quat_to_angle};
 
+use crate::climate::Climate;
+
 fn dist_angle_score(
     transform: &Transform,
     target_transform: &Transform,
@@ -20,24 +25,28 @@ fn dist_angle_score(
 
 pub fn process_sight_system(
     rapier_context: Res<RapierContext>,
+    climate: Res<Climate>,
     mut eye_query: Query<(
         Entity,
         &EyeRange,
         &EyeAngle,
         &Transform,
         &Mind,
+        &Relations,
         &mut Vision,
         &AgeEfficiency,
     )>,
-    bug_query: Query<(&Transform, &Mind)>,
-    plant_query: Query<&Transform, With<Plant>>,
+    bug_query: Query<(&Transform, &Mind, &Relations)>,
+    plant_query: Query<(&Transform, Option<&PlantType>), With<Plant>>,
     meat_query: Query<&Transform, With<Meat>>,
 ) {
     let resolution = f32::to_radians(5.0);
-    for (entity, eye_range, eye_angle, transform, mind, mut vision, age_efficiency) in
+    let sight_multiplier = climate.sight_multiplier();
+    let plant_type_count = WorldConfig::global().plant_types().len();
+    for (entity, eye_range, eye_angle, transform, mind, relations, mut vision, age_efficiency) in
         eye_query.iter_mut()
     {
-        let range = **eye_range * **age_efficiency;
+        let range = **eye_range * **age_efficiency * sight_multiplier;
         let filter = QueryFilter::new().exclude_collider(entity);
         let ray_pos = transform.translation.truncate();
 
@@ -56,15 +65,18 @@ pub fn process_sight_system(
 
         let mut visible_bugs = vec![];
         for entity in cast_hits.iter() {
-            if let Ok(plant_transform) = plant_query.get(*entity) {
+            if let Ok((plant_transform, plant_type)) = plant_query.get(*entity) {
                 vision.increment_plant();
                 let scores = dist_angle_score(transform, plant_transform, range);
-                vision.set_plant_score(scores);
+                vision.set_plant_score(scores, plant_type.map_or(0, |t| **t), plant_type_count);
                 continue;
             };
-            if let Ok(bug_data) = bug_query.get(*entity) {
+            if let Ok((bug_transform, bug_mind, bug_relations)) = bug_query.get(*entity) {
                 vision.increment_bugs();
-                visible_bugs.push(bug_data);
+                if *bug_relations.parent() == Some(relations.entity().0) {
+                    vision.increment_offspring();
+                }
+                visible_bugs.push((bug_transform, bug_mind));
                 continue;
             };
             if let Ok(meat_transform) = meat_query.get(*entity) {
@@ -96,7 +108,7 @@ mod tests {
         time::TimePlugin,
     };
     use bevy_rapier2d::prelude::{NoUserData, RapierPhysicsPlugin};
-    use genesis_attributes::Genome;
+    use genesis_attributes::{Dna, Genome};
     use genesis_config::{initialize_configs, BEHAVIOUR_TICK, INPUT_NEURONS, OUTPUT_NEURONS};
     use iyes_loopless::prelude::*;
     use rand::{rngs::StdRng, SeedableRng};
@@ -112,6 +124,7 @@ mod tests {
         let uniform = Uniform::new(-500.0, 500.0);
         let mut app = App::new();
         app.init_resource::<FixedTimesteps>()
+            .init_resource::<Climate>()
             .add_plugin(TimePlugin)
             .add_plugin(RapierPhysicsPlugin::<NoUserData>::pixels_per_meter(1000.0))
             .add_fixed_timestep(BEHAVIOUR_TICK, "standard");
@@ -131,13 +144,16 @@ mod tests {
         for _ in 0..100 {
             let transform =
                 Transform::from_xyz(uniform.sample(&mut rng), uniform.sample(&mut rng), 0.0);
-            let eye_range = EyeRange::new(400.0);
-            let eye_angle = EyeAngle::new(*eye_range, &genome.eye_range);
+            let mut dna = Dna::new(&genome, &mut rng);
+            dna.eye_range = 400.0;
+            let eye_range = EyeRange::new(dna.eye_range);
+            let eye_angle = EyeAngle::new(&dna, &genome);
             let bug = (
                 transform,
                 eye_range,
                 eye_angle,
                 mind.clone(),
+                Relations::default(),
                 Vision::new(),
                 AgeEfficiency(1.0),
             );
@@ -4,9 +4,12 @@ use genesis_config as config;
 use genesis_traits::BehaviourTracker;
 use iyes_loopless::prelude::FixedTimesteps;
 
+use crate::climate::Climate;
+
 const CONST: f32 = 1.0;
 
 pub fn sensory_system(
+    climate: Res<Climate>,
     mut query: Query<
         (
             &mut mind::MindInput,
@@ -21,6 +24,11 @@ pub fn sensory_system(
         Without<Egg>,
     >,
 ) {
+    let (warmth, daylight) = if config::WorldConfig::global().climate.brain_inputs {
+        (climate.warmth(), climate.daylight())
+    } else {
+        (0.0, 0.0)
+    };
     for (mut input, output, vitality, age, vision, heart, internal_timer, stomach) in
         query.iter_mut()
     {
@@ -43,6 +51,10 @@ pub fn sensory_system(
         input[config::HEARTBEAT_INDEX] = heart.pulse();
         input[config::INTERNAL_TIMER_INDEX] = internal_timer.elapsed_secs();
         input[config::FULLNESS_INDEX] = stomach.fullness();
+        input[config::SEASON_INDEX] = warmth;
+        input[config::DAYLIGHT_INDEX] = daylight;
+        input[config::PLANT_TYPE_INDEX] = *vision.plant_type();
+        input[config::OFFSPRING_VISIBLE_INDEX] = *vision.visible_offspring() as f32;
     }
 }
 
@@ -51,6 +63,7 @@ pub fn thinking_system(
     mut query: Query<(
         &mind::MindInput,
         &mind::Mind,
+        &mind::LiveSynapses,
         &mut mind::MindOutput,
         &mut ThinkingSum,
     )>,
@@ -58,15 +71,12 @@ pub fn thinking_system(
     let cost = config::WorldConfig::global().cost_of_thought;
     let standard = timesteps.get("standard").unwrap();
 
-    for (input, bug_brain, mut output, mut thoughts) in query.iter_mut() {
+    for (input, bug_brain, live_synapses, mut output, mut thoughts) in query.iter_mut() {
         let mut result = bug_brain.activate(input).expect("Wrong length vector");
         result[config::MOVEMENT_INDEX] = result[config::MOVEMENT_INDEX].clamp(-1.0, 1.0);
         result[config::ROTATE_INDEX] = result[config::ROTATE_INDEX].clamp(-1.0, 1.0);
         output.0 = result;
-        thoughts.add_time(
-            standard.step.as_secs_f32(),
-            bug_brain.synapses().len() as f32 * cost,
-        );
+        thoughts.add_time(standard.step.as_secs_f32(), **live_synapses as f32 * cost);
     }
 }
 
@@ -96,7 +106,8 @@ mod tests {
 
         let bug_id = app
             .world
-            .spawn(test_mind)
+            .spawn(LiveSynapses::new(&test_mind))
+            .insert(test_mind)
             .insert(MindInput(vec![1.0; 10]))
             .insert(MindOutput(vec![0.0; 10]))
             .insert(ThinkingSum::new())
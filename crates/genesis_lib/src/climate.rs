@@ -0,0 +1,138 @@
+use std::f32::consts::TAU;
+
+use bevy::prelude::{Res, ResMut, Resource};
+use genesis_components::time::SimulationTime;
+use genesis_config as config;
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum Season {
+    Spring,
+    Summer,
+    Autumn,
+    Winter,
+}
+
+impl Season {
+    pub const fn as_str(&self) -> &'static str {
+        match self {
+            Self::Spring => "Spring",
+            Self::Summer => "Summer",
+            Self::Autumn => "Autumn",
+            Self::Winter => "Winter",
+        }
+    }
+}
+
+#[derive(Resource, Debug, Default, Clone, Copy, PartialEq)]
+pub struct Climate {
+    season_progress: f32,
+    day_progress: f32,
+}
+
+impl Climate {
+    pub fn from_time(time: &SimulationTime, climate_config: &config::ClimateConfig) -> Self {
+        let elapsed = time.elapsed_secs();
+        Self {
+            season_progress: (elapsed / climate_config.season_length).fract(),
+            day_progress: (elapsed / climate_config.day_length).fract(),
+        }
+    }
+
+    /// Progress through the year, from 0.0 at the start of spring to 1.0 at the end of winter.
+    pub const fn season_progress(&self) -> f32 {
+        self.season_progress
+    }
+
+    /// Progress through the day, from 0.0 at dawn to 1.0 at the next dawn.
+    pub const fn day_progress(&self) -> f32 {
+        self.day_progress
+    }
+
+    /// Warmth of the current season, 1.0 in midsummer and -1.0 in midwinter.
+    pub fn warmth(&self) -> f32 {
+        (self.season_progress * TAU).sin()
+    }
+
+    /// Amount of light, 1.0 at midday and 0.0 at midnight.
+    pub fn daylight(&self) -> f32 {
+        (self.day_progress * TAU).sin().mul_add(0.5, 0.5)
+    }
+
+    pub fn season(&self) -> Season {
+        match ((self.season_progress + 0.125) * 4.0) as usize % 4 {
+            0 => Season::Spring,
+            1 => Season::Summer,
+            2 => Season::Autumn,
+            _ => Season::Winter,
+        }
+    }
+
+    pub fn is_day(&self) -> bool {
+        self.daylight() >= 0.5
+    }
+
+    pub fn plant_spawn_multiplier(&self) -> f32 {
+        let amplitude = config::WorldConfig::global().climate.plant_spawn_amplitude;
+        amplitude.mul_add(self.warmth(), 1.0)
+    }
+
+    pub fn plant_size_multiplier(&self) -> f32 {
+        let amplitude = config::WorldConfig::global().climate.plant_size_amplitude;
+        amplitude.mul_add(self.warmth(), 1.0)
+    }
+
+    pub fn rot_rate_multiplier(&self) -> f32 {
+        let amplitude = config::WorldConfig::global().climate.rot_rate_amplitude;
+        amplitude.mul_add(self.warmth(), 1.0)
+    }
+
+    pub fn sight_multiplier(&self) -> f32 {
+        let night_sight = config::WorldConfig::global().climate.night_sight;
+        (1.0 - night_sight).mul_add(self.daylight(), night_sight)
+    }
+}
+
+pub fn update_climate_system(time: Res<SimulationTime>, mut climate: ResMut<Climate>) {
+    let new_climate = Climate::from_time(&time, &config::WorldConfig::global().climate);
+    if *climate != new_climate {
+        *climate = new_climate;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+
+    fn climate_at(secs: f32) -> Climate {
+        let climate_config = config::ClimateConfig {
+            season_length: 100.0,
+            day_length: 10.0,
+            ..Default::default()
+        };
+        let mut time = SimulationTime::default();
+        time.tick(Duration::from_secs_f32(secs));
+        Climate::from_time(&time, &climate_config)
+    }
+
+    #[test]
+    fn seasons_follow_the_year() {
+        assert_eq!(climate_at(0.0).season(), Season::Spring);
+        assert_eq!(climate_at(25.0).season(), Season::Summer);
+        assert_eq!(climate_at(50.0).season(), Season::Autumn);
+        assert_eq!(climate_at(75.0).season(), Season::Winter);
+        assert_eq!(climate_at(100.0).season(), Season::Spring);
+    }
+
+    #[test]
+    fn midday_is_brightest() {
+        let midday = climate_at(2.5);
+        let midnight = climate_at(7.5);
+
+        assert!((midday.daylight() - 1.0).abs() < 1e-5);
+        assert!(midnight.daylight().abs() < 1e-5);
+        assert!(midday.is_day());
+        assert!(!midnight.is_day());
+    }
+}
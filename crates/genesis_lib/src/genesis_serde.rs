@@ -12,12 +12,16 @@ use genesis_attributes as attributes;
 use genesis_components::{mind, time::SimulationTime};
 use genesis_config::WorldConfig;
 use genesis_ecosystem::Ecosystem;
+use genesis_spawners::{SpawnerState, Spawners};
 use serde::{Deserialize, Serialize};
 use thiserror::Error;
 
 use crate::{
-    statistics::{BugPerformance, CountStats, EnergyStats, FamilyTree},
-    ui::{LoadBugEvent, SaveBugEvent, Selected},
+    statistics::{
+        AttributeStats, BugPerformance, CombatStats, CountStats, EnergyFluxStats, EnergyStats,
+        FamilyTree, FoodWeb,
+    },
+    ui::{BrainExportFormat, ExportBrainEvent, LoadBugEvent, SaveBugEvent, Selected},
 };
 
 #[derive(Debug, Error)]
@@ -85,12 +89,54 @@ pub fn save_bug_system(
     time.update();
 }
 
+fn export_brain(bug_brain: &mind::Mind, format: BrainExportFormat) -> Result<(), BugSerdeError> {
+    let (file_name, content) = match format {
+        BrainExportFormat::Dot => (
+            "brain.dot",
+            bug_brain.to_dot(*WorldConfig::global().brain_mutations.max_weight()),
+        ),
+        BrainExportFormat::Json => (
+            "brain.json",
+            serde_json::to_string_pretty(&bug_brain.to_graph())?,
+        ),
+    };
+    let path = std::env::current_dir()?;
+    let Some(res) = rfd::FileDialog::new()
+        .set_file_name(file_name)
+        .set_directory(path)
+        .save_file()
+    else {
+        return Ok(());
+    };
+    fs::write(res, content)?;
+    Ok(())
+}
+
+pub fn export_brain_system(
+    mut time: ResMut<Time>,
+    bug_query: Query<&mind::Mind, With<Selected>>,
+    mut ev_export_brain: EventReader<ExportBrainEvent>,
+) {
+    let Some(ExportBrainEvent(format)) = ev_export_brain.iter().last() else {
+        return;
+    };
+    let Ok(bug_brain) = bug_query.get_single() else {
+        return;
+    };
+    if let Err(e) = export_brain(bug_brain, *format) {
+        warn!("Could not export brain. Please try again. {e}")
+    };
+    time.update();
+}
+
 fn load_bug_blueprint(genome: &attributes::Genome) -> Result<Option<BugBlueprint>, BugSerdeError> {
     let Some(path) = rfd::FileDialog::new().pick_file() else {
         return Ok(None);
     };
     let content = fs::read(path)?;
-    let blueprint: BugBlueprint = serde_json::from_slice(&content)?;
+    let mut blueprint: BugBlueprint = serde_json::from_slice(&content)?;
+    blueprint.mind.migrate()?;
+    blueprint.dna.fill_missing(genome, &mut rand::thread_rng());
     blueprint.validate(genome)?;
     Ok(Some(blueprint))
 }
@@ -118,8 +164,18 @@ pub struct SimulationSerializer {
     ecosystem: Ecosystem,
     count_stats: CountStats,
     energy_stats: EnergyStats,
+    #[serde(default)]
+    energy_flux_stats: EnergyFluxStats,
+    #[serde(default)]
+    attribute_stats: AttributeStats,
+    #[serde(default)]
+    combat_stats: CombatStats,
+    #[serde(default)]
+    food_web: FoodWeb,
     bug_performance: BugPerformance,
     family_tree: FamilyTree,
+    #[serde(default)]
+    spawners: Vec<SpawnerState>,
 }
 
 impl SimulationSerializer {
@@ -129,16 +185,26 @@ impl SimulationSerializer {
         let ecosystem = world.get_resource::<Ecosystem>().unwrap().to_owned();
         let count_stats = world.get_resource::<CountStats>().unwrap().to_owned();
         let energy_stats = world.get_resource::<EnergyStats>().unwrap().to_owned();
+        let energy_flux_stats = world.get_resource::<EnergyFluxStats>().unwrap().to_owned();
+        let attribute_stats = world.get_resource::<AttributeStats>().unwrap().to_owned();
+        let combat_stats = world.get_resource::<CombatStats>().unwrap().to_owned();
+        let food_web = world.get_resource::<FoodWeb>().unwrap().to_owned();
         let bug_performance = world.get_resource::<BugPerformance>().unwrap().to_owned();
         let family_tree = world.get_resource::<FamilyTree>().unwrap().to_owned();
+        let spawners = world.get_resource::<Spawners>().unwrap().states();
         Self {
             config,
             sim_time,
             ecosystem,
             count_stats,
             energy_stats,
+            energy_flux_stats,
+            attribute_stats,
+            combat_stats,
+            food_web,
             bug_performance,
             family_tree,
+            spawners,
         }
     }
 }
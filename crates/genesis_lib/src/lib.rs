@@ -15,10 +15,13 @@ use iyes_loopless::prelude::*;
 use simulation::SimulationPlugin;
 extern crate test;
 
+mod auditor;
 mod behaviour;
+mod climate;
 mod conditions;
 mod genesis_serde;
 mod lifecycle;
+mod recorder;
 mod setup;
 mod simulation;
 mod spawning;
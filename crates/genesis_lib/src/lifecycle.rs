@@ -13,7 +13,11 @@ use genesis_config as config;
 use genesis_ecosystem as ecosystem;
 use genesis_traits::AttributeDisplay;
 
-use crate::{spawning, statistics};
+use crate::{
+    auditor::{EnergyFlow, EnergyLedger},
+    climate::Climate,
+    spawning, statistics,
+};
 
 pub fn transition_to_adult_system(
     mut commands: Commands,
@@ -34,27 +38,57 @@ type EggQuery<'a> = (
     &'a mind::Mind,
     &'a Sprite,
     &'a attributes::HatchSize,
+    &'a attributes::AspectRatio,
+    &'a attributes::BodyDensity,
 );
 
 pub fn hatch_egg_system(
     mut commands: Commands,
     asset_server: Res<AssetServer>,
     mut ecosystem: ResMut<ecosystem::Ecosystem>,
+    mut ledger: ResMut<EnergyLedger>,
     mut hatch_query: Query<EggQuery, With<Egg>>,
 ) {
-    for (entity, age, hatch_age, mut egg_energy, mind, sprite, hatch_size) in hatch_query.iter_mut()
+    let prune = config::WorldConfig::global().prune_brains_on_hatch;
+    for (
+        entity,
+        age,
+        hatch_age,
+        mut egg_energy,
+        mind,
+        sprite,
+        hatch_size,
+        aspect_ratio,
+        body_density,
+    ) in hatch_query.iter_mut()
     {
         if age.elapsed_secs() < **hatch_age {
             continue;
         }
         commands.entity(entity).remove::<spawning::EggBundle>();
+        let mind = if prune {
+            let pruned = mind::Mind(mind.pruned());
+            commands.entity(entity).insert(pruned.clone());
+            pruned
+        } else {
+            mind.clone()
+        };
         let hatching_entity = commands.entity(entity);
+        let energy = egg_energy.move_all_energy();
+        let hatched_amount = energy.amount();
         let leftover_energy = spawning::spawn_bug(
             &asset_server,
-            egg_energy.move_all_energy(),
-            (mind.clone(), &sprite.color, hatch_size),
+            energy,
+            (mind, &sprite.color, hatch_size, aspect_ratio, body_density),
             hatching_entity,
         );
+        let leftover_amount = leftover_energy.amount();
+        ledger.record(
+            EnergyFlow::Hatched,
+            "hatch_egg_system",
+            hatched_amount - leftover_amount,
+        );
+        ledger.record(EnergyFlow::Burnt, "hatch_egg_system", leftover_amount);
         ecosystem.return_energy(leftover_energy);
     }
 }
@@ -62,25 +96,54 @@ pub fn hatch_egg_system(
 pub fn kill_bug_system(
     asset_server: Res<AssetServer>,
     mut commands: Commands,
+    mut ecosystem: ResMut<ecosystem::Ecosystem>,
     mut family_tree: ResMut<statistics::FamilyTree>,
+    mut ledger: ResMut<EnergyLedger>,
     mut query: Query<(
         Entity,
         &mut body::Vitality,
+        &mut eat::Stomach,
+        &mut BurntEnergy,
         &attributes::DeathAge,
         &time::Age,
         &Relations,
+        &Lineage,
         &Transform,
         &dyn AttributeDisplay,
     )>,
 ) {
-    for (entity, mut vitality, death_age, age, relation, transform, attrs) in query.iter_mut() {
+    for (
+        entity,
+        mut vitality,
+        mut stomach,
+        mut burnt_energy,
+        death_age,
+        age,
+        relation,
+        lineage,
+        transform,
+        attrs,
+    ) in query.iter_mut()
+    {
         if vitality.health().amount() == 0 || **death_age < age.elapsed_secs() {
-            let meat_energy = vitality.take_all_energy();
+            let returned_energy = burnt_energy.return_energy();
+            ledger.record(
+                EnergyFlow::Burnt,
+                "kill_bug_system",
+                returned_energy.amount(),
+            );
+            ecosystem.return_energy(returned_energy);
+
+            // Everything else the bug was holding becomes meat, including undigested food.
+            let mut meat_energy = vitality.take_all_energy();
+            meat_energy.add_energy(stomach.take_all_energy());
+            ledger.record(EnergyFlow::Killed, "kill_bug_system", meat_energy.amount());
             spawning::spawn_meat(
                 &mut commands,
                 &asset_server,
                 meat_energy,
                 transform.translation,
+                Some(MeatSource::new(relation, *lineage)),
             );
             family_tree.add_dead_relation(relation, attrs);
             commands.entity(entity).despawn_recursive();
@@ -91,18 +154,21 @@ pub fn kill_bug_system(
 pub fn kill_egg_system(
     asset_server: Res<AssetServer>,
     mut commands: Commands,
+    mut ledger: ResMut<EnergyLedger>,
     mut ev_egg: EventReader<DeadEggEvent>,
-    mut egg_query: Query<(Entity, &Transform, &mut EggEnergy)>,
+    mut egg_query: Query<(Entity, &Transform, &mut EggEnergy, &Relations, &Lineage)>,
 ) {
     for ev in ev_egg.iter() {
         if let Ok(egg) = egg_query.get_mut(ev.0) {
-            let (egg_entity, egg_transform, mut egg_energy) = egg;
+            let (egg_entity, egg_transform, mut egg_energy, relations, lineage) = egg;
             let meat_energy = egg_energy.move_all_energy();
+            ledger.record(EnergyFlow::Killed, "kill_egg_system", meat_energy.amount());
             spawning::spawn_meat(
                 &mut commands,
                 &asset_server,
                 meat_energy,
                 egg_transform.translation,
+                Some(MeatSource::new(relations, *lineage)),
             );
             commands.entity(egg_entity).despawn_recursive();
         }
@@ -111,17 +177,67 @@ pub fn kill_egg_system(
 
 pub fn rot_meat_system(
     mut ecosystem: ResMut<ecosystem::Ecosystem>,
+    mut ledger: ResMut<EnergyLedger>,
+    climate: Res<Climate>,
     mut meat_query: Query<
         (&mut Sprite, &mut Collider, &mut Size, &mut ecosystem::Food),
         With<Meat>,
     >,
 ) {
-    let rot_rate = config::WorldConfig::global().meat.rot_rate;
+    let rot_rate = (config::WorldConfig::global().meat.rot_rate as f32
+        * climate.rot_rate_multiplier())
+    .round() as usize;
     for (mut sprite, mut collider, mut size, mut meat) in meat_query.iter_mut() {
         let rotting_energy = meat.take_energy(rot_rate);
+        ledger.record(
+            EnergyFlow::Rotted,
+            "rot_meat_system",
+            rotting_energy.amount(),
+        );
         **size = meat.size();
         sprite.custom_size = Some(spawning::food_sprite_size(&size));
         *collider = spawning::food_collider(&size);
         ecosystem.return_energy(rotting_energy);
     }
 }
+
+pub fn grow_plants_system(
+    mut ecosystem: ResMut<ecosystem::Ecosystem>,
+    mut ledger: ResMut<EnergyLedger>,
+    climate: Res<Climate>,
+    mut plant_query: Query<
+        (
+            &PlantType,
+            &mut Sprite,
+            &mut Collider,
+            &mut Size,
+            &mut ecosystem::Food,
+            Option<&Grazed>,
+        ),
+        With<Plant>,
+    >,
+) {
+    let config_instance = config::WorldConfig::global();
+    let energy_floor = spawning::plant_energy_floor();
+    let seasonal_growth = climate.plant_spawn_multiplier();
+    for (plant_type, mut sprite, mut collider, mut size, mut plant, grazed) in
+        plant_query.iter_mut()
+    {
+        let plant_config = config_instance.plant_type(**plant_type);
+        if grazed.is_some() && !plant_config.regrows {
+            continue;
+        }
+        let growth =
+            (plant_config.growth_rate * seasonal_growth).min(plant_config.max_size - **size);
+        if growth <= 0.0 || ecosystem.available_energy().amount() <= energy_floor {
+            continue;
+        }
+        let growth_energy = (growth * plant_config.energy_density as f32) as usize;
+        let Some(energy) = ecosystem.request_energy(growth_energy) else { continue };
+        ledger.record(EnergyFlow::Planted, "grow_plants_system", energy.amount());
+        plant.add_energy(energy);
+        **size = plant.size();
+        sprite.custom_size = Some(spawning::food_sprite_size(&size));
+        *collider = spawning::food_collider(&size);
+    }
+}
@@ -0,0 +1,124 @@
+use std::{collections::VecDeque, fmt::Write, fs};
+
+use bevy::{
+    log::warn,
+    prelude::{Entity, EventReader, Query, Res, ResMut, Resource, With},
+    time::Time,
+};
+use genesis_components::mind;
+
+use crate::ui::{neuron_name, ExportActivationsEvent, Selected};
+
+/// Twenty seconds of activations at the standard tick rate.
+const MAX_SAMPLES: usize = 400;
+
+/// Every neuron's activation for the selected bug, one sample per standard tick.
+#[derive(Resource, Debug, Default)]
+pub struct ActivationRecorder {
+    entity: Option<Entity>,
+    samples: VecDeque<Vec<f32>>,
+}
+
+impl ActivationRecorder {
+    fn record(&mut self, entity: Entity, values: Vec<f32>) {
+        if self.entity != Some(entity) {
+            self.entity = Some(entity);
+            self.samples.clear();
+        }
+        if self.samples.len() == MAX_SAMPLES {
+            self.samples.pop_front();
+        }
+        self.samples.push_back(values);
+    }
+
+    pub const fn entity(&self) -> Option<Entity> {
+        self.entity
+    }
+
+    pub fn neuron_count(&self) -> usize {
+        self.samples.back().map_or(0, Vec::len)
+    }
+
+    /// The recorded values of one neuron, oldest first.
+    pub fn trace(&self, neuron: usize) -> Vec<f32> {
+        self.samples
+            .iter()
+            .map(|sample| sample.get(neuron).copied().unwrap_or_default())
+            .collect()
+    }
+
+    fn to_csv(&self, name: impl Fn(usize) -> String) -> String {
+        let mut csv = String::from("tick");
+        for neuron in 0..self.neuron_count() {
+            let _ = write!(csv, ",{}", name(neuron));
+        }
+        csv.push('\n');
+        for (tick, sample) in self.samples.iter().enumerate() {
+            let _ = write!(csv, "{tick}");
+            for value in sample {
+                let _ = write!(csv, ",{value}");
+            }
+            csv.push('\n');
+        }
+        csv
+    }
+}
+
+pub fn record_activations_system(
+    mut recorder: ResMut<ActivationRecorder>,
+    bug_query: Query<(Entity, &mind::MindInput, &mind::Mind), With<Selected>>,
+) {
+    let Ok((entity, input, bug_brain)) = bug_query.get_single() else {
+        return;
+    };
+    if let Ok(values) = bug_brain.activate_all(input) {
+        recorder.record(entity, values);
+    }
+}
+
+pub fn export_activations_system(
+    mut time: ResMut<Time>,
+    recorder: Res<ActivationRecorder>,
+    mut ev_export: EventReader<ExportActivationsEvent>,
+) {
+    if ev_export.iter().last().is_none() {
+        return;
+    };
+    let path = std::env::current_dir().unwrap();
+    let Some(res) = rfd::FileDialog::new()
+        .set_file_name("activations.csv")
+        .set_directory(path)
+        .save_file()
+    else {
+        return;
+    };
+    if let Err(e) = fs::write(res, recorder.to_csv(neuron_name)) {
+        warn!("Could not export activations. Please try again. {e}")
+    };
+    time.update();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn recorder_is_bounded_and_resets_on_a_new_bug() {
+        let mut recorder = ActivationRecorder::default();
+        let first = Entity::from_raw(1);
+        for i in 0..MAX_SAMPLES + 5 {
+            recorder.record(first, vec![i as f32, 1.0]);
+        }
+        assert_eq!(recorder.trace(0).len(), MAX_SAMPLES);
+        assert_eq!(recorder.trace(0)[0], 5.0);
+
+        let second = Entity::from_raw(2);
+        recorder.record(second, vec![0.5, 0.25, 0.75]);
+        assert_eq!(recorder.entity(), Some(second));
+        assert_eq!(recorder.neuron_count(), 3);
+        assert_eq!(
+            recorder.to_csv(|neuron| format!("n{neuron}")),
+            "tick,n0,n1,n2\n0,0.5,0.25,0.75\n"
+        );
+    }
+}
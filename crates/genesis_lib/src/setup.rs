@@ -2,20 +2,24 @@ use std::fs;
 
 use bevy::{
     prelude::{
-        default, info, AssetServer, Camera2dBundle, Color, Commands, Entity, Query, Res, ResMut,
-        SystemSet, Transform, Vec2, With, Without, World,
+        default, info, warn, AssetServer, Camera2dBundle, Color, Commands, Entity, Query, Res,
+        ResMut, SystemSet, Transform, Vec2, With, Without, World,
     },
     scene::DynamicSceneBundle,
 };
-use bevy_rapier2d::prelude::RapierConfiguration;
-use genesis_attributes::Genome;
-use genesis_components::{body::OriginalColor, mind, time, Egg, Plant, Size};
+use bevy_rapier2d::prelude::{ColliderMassProperties, RapierConfiguration};
+use genesis_attributes::{AspectRatio, Dna, GeneBundle, Genome};
+use genesis_components::{
+    body::OriginalColor, mind, time, Egg, Lineage, Plant, PlantType, Relations, Size,
+};
 use genesis_config as config;
 use genesis_ecosystem as ecosystem;
 use genesis_spawners::Spawners;
 use iyes_loopless::prelude::*;
 
-use crate::{genesis_serde::SimulationSerializer, spawning, statistics, SimState};
+use crate::{
+    auditor, climate, genesis_serde::SimulationSerializer, spawning, statistics, SimState,
+};
 
 fn camera_setup(mut commands: Commands) {
     commands.spawn(Camera2dBundle::default());
@@ -30,8 +34,12 @@ fn resource_setup(mut commands: Commands) {
 
     let config_instance = config::WorldConfig::global();
 
-    let spawners = Spawners::from_configs(&config_instance.spawners).unwrap();
-    let plant_spawn_size = spawning::PlantSizeRandomiser::new(config_instance.plant.size_range);
+    let spawners = Spawners::from_configs(
+        &config_instance.spawners,
+        config_instance.plant_species.len() + 1,
+    )
+    .unwrap();
+    let plant_spawn_size = spawning::PlantSizeRandomiser::new(config_instance.plant_types());
     let ecosystem = ecosystem::Ecosystem::new(config_instance.world_energy);
 
     commands.insert_resource(spawners);
@@ -39,11 +47,18 @@ fn resource_setup(mut commands: Commands) {
     commands.insert_resource(ecosystem);
     commands.init_resource::<Genome>();
     commands.init_resource::<time::SimulationTime>();
+    commands.init_resource::<climate::Climate>();
     commands.init_resource::<statistics::FamilyTree>();
     commands.insert_resource(statistics::CountStats::default());
     commands.insert_resource(statistics::BugPerformance::default());
     commands.insert_resource(statistics::EnergyStats::default());
+    commands.insert_resource(statistics::EnergyFluxStats::default());
+    commands.insert_resource(statistics::AttributeStats::default());
+    commands.insert_resource(statistics::CombatStats::default());
+    commands.insert_resource(statistics::FoodWeb::default());
     commands.insert_resource(mind::MindThresholds::new(&config_instance.brain_mutations));
+    commands.init_resource::<auditor::EnergyLedger>();
+    commands.init_resource::<auditor::EnergyAudit>();
 }
 
 pub fn sim_setup_system_set() -> SystemSet {
@@ -66,23 +81,43 @@ fn load_simulation_system(world: &mut World) {
 
     let serialize_simulation = fs::read(res.join("resources.ron")).unwrap();
     let simulation: SimulationSerializer = ron::de::from_bytes(&serialize_simulation).unwrap();
-    genesis_config::initialize_configs(Some(simulation.config().clone()));
+    let mut loaded_config = simulation.config().clone();
+    // Saves from before the current neuron layout can point at neurons that have since moved.
+    loaded_config.starting_synapses.retain(|synapse| {
+        config::starting_synapse_issue(*synapse).map_or(true, |issue| {
+            warn!("Dropping a saved starting synapse. {issue}");
+            false
+        })
+    });
+    genesis_config::initialize_configs(Some(loaded_config));
     world.insert_resource(simulation.sim_time().clone());
     world.insert_resource(simulation.ecosystem().clone());
     world.insert_resource(simulation.count_stats().clone());
     world.insert_resource(simulation.energy_stats().clone());
+    world.insert_resource(simulation.energy_flux_stats().clone());
+    world.insert_resource(simulation.attribute_stats().clone());
+    world.insert_resource(simulation.combat_stats().clone());
+    world.insert_resource(simulation.food_web().clone());
     world.insert_resource(simulation.bug_performance().clone());
     world.insert_resource(simulation.family_tree().clone());
 
     let config_instance = genesis_config::WorldConfig::global();
 
-    let spawners = Spawners::from_configs(&config_instance.spawners).unwrap();
-    let plant_spawn_size = spawning::PlantSizeRandomiser::new(config_instance.plant.size_range);
+    let mut spawners = Spawners::from_configs(
+        &config_instance.spawners,
+        config_instance.plant_species.len() + 1,
+    )
+    .unwrap();
+    spawners.set_states(simulation.spawners());
+    let plant_spawn_size = spawning::PlantSizeRandomiser::new(config_instance.plant_types());
 
     world.insert_resource(spawners);
     world.insert_resource(plant_spawn_size);
     world.init_resource::<Genome>();
+    world.init_resource::<climate::Climate>();
     world.insert_resource(mind::MindThresholds::new(&config_instance.brain_mutations));
+    world.init_resource::<auditor::EnergyLedger>();
+    world.init_resource::<auditor::EnergyAudit>();
     let asset_server = world.get_resource::<AssetServer>().unwrap();
     world.spawn(DynamicSceneBundle {
         scene: asset_server.load(res.join("scene.scn.ron")),
@@ -90,21 +125,48 @@ fn load_simulation_system(world: &mut World) {
     });
 }
 
-fn mind_layout_system(mut commands: Commands, mind_query: Query<(Entity, &mind::Mind)>) {
-    for (entity, mind) in &mind_query {
-        let mind_layout = mind::MindLayout::new(mind);
-        commands.entity(entity).insert(mind_layout);
+/// Pads brains saved by older versions and lays them out, resizing their inputs and outputs
+/// to match.
+fn mind_layout_system(mut commands: Commands, mut mind_query: Query<(Entity, &mut mind::Mind)>) {
+    for (entity, mut mind) in &mut mind_query {
+        match mind.migrate() {
+            Ok(true) => {
+                commands.entity(entity).insert(mind::MindBundle::new(&mind));
+            }
+            Ok(false) => {
+                commands
+                    .entity(entity)
+                    .insert(mind::MindLayout::new(&mind))
+                    .insert(mind::LiveSynapses::new(&mind));
+            }
+            Err(e) => {
+                warn!("Removing a bug that could not be loaded: {e}");
+                commands.entity(entity).despawn();
+            }
+        }
+    }
+}
+
+/// Draws any genes missing from a saved organism and gives it their components.
+fn fill_missing_genes(commands: &mut Commands, entity: Entity, dna: &mut Dna, genome: &Genome) {
+    if dna.fill_missing(genome, &mut rand::thread_rng()) {
+        commands.entity(entity).insert(GeneBundle::new(dna));
     }
 }
 
 fn add_missing_components_system(
     mut commands: Commands,
     asset_server: Res<AssetServer>,
-    plant_query: Query<(Entity, &Size, &Transform), With<Plant>>,
-    egg_query: Query<(Entity, &OriginalColor, &Transform, &Size), With<Egg>>,
-    bug_query: Query<(Entity, &Size, &mind::Mind), Without<Egg>>,
+    genome: Res<Genome>,
+    plant_query: Query<(Entity, &Size, &Transform, Option<&PlantType>), With<Plant>>,
+    mut egg_query: Query<(Entity, &OriginalColor, &Transform, &Size, &mut Dna), With<Egg>>,
+    mut bug_query: Query<(Entity, &Size, &mind::Mind, &mut Dna), Without<Egg>>,
+    lineage_query: Query<(Entity, &Relations), Without<Lineage>>,
 ) {
-    for (entity, size, transform) in &plant_query {
+    let config_instance = config::WorldConfig::global();
+    for (entity, size, transform, plant_type) in &plant_query {
+        let plant_type = plant_type.copied().unwrap_or_default();
+        let plant_config = config_instance.plant_type(*plant_type);
         commands
             .entity(entity)
             .insert(spawning::food_collider(size))
@@ -112,11 +174,13 @@ fn add_missing_components_system(
                 &asset_server,
                 size,
                 transform.translation,
-                Color::GREEN,
-            ));
+                spawning::plant_color(plant_config),
+            ))
+            .insert(plant_type);
     }
 
-    for (entity, color, transform, size) in &egg_query {
+    for (entity, color, transform, size, mut dna) in &mut egg_query {
+        fill_missing_genes(&mut commands, entity, &mut dna, &genome);
         commands
             .entity(entity)
             .insert(spawning::egg_collider(size))
@@ -129,17 +193,28 @@ fn add_missing_components_system(
     }
 
     let basic_color = Color::WHITE;
-    for (entity, size, mind) in &bug_query {
+    for (entity, size, mind, mut dna) in &mut bug_query {
+        fill_missing_genes(&mut commands, entity, &mut dna, &genome);
+        let aspect_ratio = AspectRatio::new(dna.aspect_ratio);
         commands
             .entity(entity)
-            .insert(spawning::bug_collider(size))
+            .insert(spawning::bug_collider(size, &aspect_ratio))
+            .insert(ColliderMassProperties::Density(dna.body_density))
             .insert(spawning::bug_sprite_bundle(
                 &asset_server,
                 size,
+                &aspect_ratio,
                 &basic_color,
                 mind.color(),
             ));
     }
+
+    // Organisms saved before lineages were tracked each found their own.
+    for (entity, relations) in &lineage_query {
+        commands
+            .entity(entity)
+            .insert(Lineage(relations.entity().0));
+    }
 }
 
 pub fn load_simulation_system_set() -> SystemSet {
@@ -153,3 +228,40 @@ pub fn load_simulation_setup_system_set() -> SystemSet {
         .with_system(physics_setup)
         .into()
 }
+
+#[cfg(test)]
+mod tests {
+    use bevy::app::App;
+    use genesis_attributes::Venom;
+
+    use super::*;
+
+    #[test]
+    fn filling_missing_genes_gives_their_components() {
+        config::initialize_configs(None);
+        let genome = Genome::new();
+        let complete = Dna::new(&genome, &mut rand::thread_rng());
+        let mut old = complete;
+        old.venom = Dna::default().venom;
+        let mut app = App::new();
+        app.insert_resource(genome).add_system(
+            |mut commands: Commands, genome: Res<Genome>, mut query: Query<(Entity, &mut Dna)>| {
+                for (entity, mut dna) in &mut query {
+                    fill_missing_genes(&mut commands, entity, &mut dna, &genome);
+                }
+            },
+        );
+        let old = app.world.spawn(old).id();
+        let complete = app.world.spawn(complete).id();
+
+        app.update();
+
+        let genome = app.world.resource::<Genome>();
+        let dna = app.world.get::<Dna>(old).unwrap();
+        assert!(dna.validate(genome).is_ok());
+        let venom = app.world.get::<Venom>(old).unwrap();
+        assert!(!venom.is_nan());
+        assert_eq!(**venom, dna.venom);
+        assert!(app.world.get::<Venom>(complete).is_none());
+    }
+}
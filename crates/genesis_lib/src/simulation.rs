@@ -7,7 +7,8 @@ use genesis_config as config;
 use iyes_loopless::prelude::*;
 
 use crate::{
-    behaviour, conditions, genesis_serde, lifecycle, setup, spawning, statistics, ui, SimState,
+    auditor, behaviour, climate, conditions, genesis_serde, lifecycle, recorder, setup, spawning,
+    statistics, ui, SimState,
 };
 
 #[derive(Resource, Debug)]
@@ -25,6 +26,14 @@ impl Default for SimulationSpeed {
     }
 }
 
+pub fn climate_system_set() -> SystemSet {
+    ConditionSet::new()
+        .run_if_not(conditions::is_paused)
+        .run_in_state(SimState::Simulation)
+        .with_system(climate::update_climate_system)
+        .into()
+}
+
 pub fn plant_system_set() -> SystemSet {
     ConditionSet::new()
         .run_if_not(conditions::is_paused)
@@ -69,6 +78,15 @@ pub fn rot_meat_system_set() -> SystemSet {
         .into()
 }
 
+pub fn plant_lifecycle_system_set() -> SystemSet {
+    ConditionSet::new()
+        .run_if_not(conditions::is_paused)
+        .run_in_state(SimState::Simulation)
+        .with_system(lifecycle::grow_plants_system)
+        .with_system(spawning::seed_plants_system)
+        .into()
+}
+
 pub fn nearest_spawner_system_set() -> SystemSet {
     ConditionSet::new()
         .run_if_not(conditions::is_paused)
@@ -77,6 +95,32 @@ pub fn nearest_spawner_system_set() -> SystemSet {
         .into()
 }
 
+pub fn spawner_movement_system_set() -> SystemSet {
+    ConditionSet::new()
+        .run_if_not(conditions::is_paused)
+        .run_in_state(SimState::Simulation)
+        .with_system(spawning::move_spawners_system)
+        .into()
+}
+
+/// Audits at the end of every frame, once every system that moves energy has run.
+pub fn energy_audit_system_set() -> SystemSet {
+    ConditionSet::new()
+        .run_if_not(conditions::is_paused)
+        .run_in_state(SimState::Simulation)
+        .with_system(auditor::audit_energy_system)
+        .into()
+}
+
+pub fn activation_recorder_system_set() -> SystemSet {
+    ConditionSet::new()
+        .after("thinking")
+        .run_if_not(conditions::is_paused)
+        .run_in_state(SimState::Simulation)
+        .with_system(recorder::record_activations_system)
+        .into()
+}
+
 pub fn family_tree_system_set() -> SystemSet {
     ConditionSet::new()
         .run_in_state(SimState::Simulation)
@@ -89,6 +133,8 @@ pub fn bug_serde_system_set() -> SystemSet {
         .run_in_state(SimState::Simulation)
         .with_system(genesis_serde::load_bug_system)
         .with_system(genesis_serde::save_bug_system)
+        .with_system(genesis_serde::export_brain_system)
+        .with_system(recorder::export_activations_system)
         .into()
 }
 
@@ -120,14 +166,20 @@ impl Plugin for SimulationPlugin {
             .init_resource::<genesis_serde::LoadedBlueprint>()
             .insert_resource(config::BACKGROUND)
             .init_resource::<SimulationSpeed>()
+            .init_resource::<recorder::ActivationRecorder>()
+            .add_system_set(climate_system_set())
+            .add_system_set(spawner_movement_system_set())
             .add_system_set(plant_system_set())
             .add_system_set(food_system_set())
             .add_system_set(bug_serde_system_set())
+            .add_system_set_to_stage(CoreStage::Last, energy_audit_system_set())
             .add_fixed_timestep(Duration::from_secs(10), "family_tree")
             .add_fixed_timestep(Duration::from_millis(100), "spawner_stats")
             .add_fixed_timestep_system_set("family_tree", 0, family_tree_system_set())
             .add_fixed_timestep_system_set("spawner_stats", 0, nearest_spawner_system_set())
             .add_fixed_timestep_system_set("very_slow", 0, rot_meat_system_set())
-            .add_fixed_timestep_system_set("standard", 0, lifecycle_system_set());
+            .add_fixed_timestep_system_set("very_slow", 0, plant_lifecycle_system_set())
+            .add_fixed_timestep_system_set("standard", 0, lifecycle_system_set())
+            .add_fixed_timestep_system_set("standard", 0, activation_recorder_system_set());
     }
 }
@@ -1,3 +1,5 @@
+use std::f32::consts::PI;
+
 use bevy::{
     ecs::system::EntityCommands,
     prelude::{
@@ -7,7 +9,8 @@ use bevy::{
     sprite::{Sprite, SpriteBundle},
 };
 use bevy_rapier2d::prelude::{
-    ActiveEvents, Collider, ColliderMassProperties, Damping, ExternalImpulse, RigidBody, Velocity,
+    ActiveEvents, Collider, ColliderMassProperties, Damping, ExternalImpulse, QueryFilter,
+    RapierContext, RigidBody, Velocity,
 };
 use genesis_attributes as attributes;
 use genesis_components as components;
@@ -16,15 +19,29 @@ use genesis_components::{
 };
 use genesis_config as config;
 use genesis_ecosystem as ecosystem;
+use genesis_maths::polars_to_cart;
 use genesis_spawners::Spawners;
 use genesis_traits::BehaviourTracker;
+use rand::Rng;
 use rand_distr::{Distribution, Uniform};
 
-type BugParts<'a> = (mind::Mind, &'a Color, &'a attributes::HatchSize);
+use crate::{
+    auditor::{EnergyFlow, EnergyLedger},
+    climate::Climate,
+};
+
+type BugParts<'a> = (
+    mind::Mind,
+    &'a Color,
+    &'a attributes::HatchSize,
+    &'a attributes::AspectRatio,
+    &'a attributes::BodyDensity,
+);
 
 pub fn bug_sprite_bundle(
     asset_server: &Res<AssetServer>,
     size: &Size,
+    aspect_ratio: &attributes::AspectRatio,
     color: &Color,
     mind_color: Color,
 ) -> impl Bundle {
@@ -38,7 +55,7 @@ pub fn bug_sprite_bundle(
 
     let texture: Handle<Image> = asset_server.load("sprite.png");
     let sprite = Sprite {
-        custom_size: Some(bug_sprite_size(size)),
+        custom_size: Some(bug_sprite_size(size, aspect_ratio)),
         color: current_color,
         ..default()
     };
@@ -46,16 +63,24 @@ pub fn bug_sprite_bundle(
     (texture, sprite, original_color)
 }
 
-pub fn bug_collider(size: &Size) -> Collider {
+/// A capsule along the bug's heading, stretched by the aspect ratio while keeping roughly the
+/// same area.
+pub fn bug_collider(size: &Size, aspect_ratio: &attributes::AspectRatio) -> Collider {
+    let stretch = aspect_ratio.sqrt();
+    let radius = **size / 3.5 / stretch;
+    let half_length = (**size / 5.5 + **size / 3.5)
+        .mul_add(stretch, -radius)
+        .max(0.0);
     Collider::capsule(
-        Vec2::new(0.0, -**size / 5.5),
-        Vec2::new(0.0, **size / 5.5),
-        **size / 3.5,
+        Vec2::new(0.0, -half_length),
+        Vec2::new(0.0, half_length),
+        radius,
     )
 }
 
-pub fn bug_sprite_size(size: &Size) -> Vec2 {
-    Vec2::splat(**size)
+pub fn bug_sprite_size(size: &Size, aspect_ratio: &attributes::AspectRatio) -> Vec2 {
+    let stretch = aspect_ratio.sqrt();
+    Vec2::new(**size / stretch, **size * stretch)
 }
 
 pub fn spawn_bug(
@@ -64,7 +89,7 @@ pub fn spawn_bug(
     bug_parts: BugParts,
     mut hatching_entity: EntityCommands,
 ) -> ecosystem::Energy {
-    let (mind, egg_color, hatch_size) = bug_parts;
+    let (mind, egg_color, hatch_size, aspect_ratio, body_density) = bug_parts;
     let mind_bundle = mind::MindBundle::new(&mind);
 
     let size = Size::new(**hatch_size);
@@ -75,11 +100,13 @@ pub fn spawn_bug(
         .insert(bug_sprite_bundle(
             asset_server,
             &size,
+            aspect_ratio,
             egg_color,
             mind.color(),
         ))
         .insert(ActiveEvents::COLLISION_EVENTS)
-        .insert(bug_collider(&size))
+        .insert(bug_collider(&size, aspect_ratio))
+        .insert(ColliderMassProperties::Density(**body_density))
         .insert(SizeMultiplier::new(&size))
         .insert(components::Juvenile)
         .insert(vitality)
@@ -152,7 +179,7 @@ pub fn spawn_egg(
     dna: attributes::Dna,
     mind: mind::Mind,
     generation: components::Generation,
-    parent_id: Option<Entity>,
+    parent: Option<(Entity, components::Lineage)>,
 ) -> Entity {
     let size = Size::new(16.0);
 
@@ -184,7 +211,11 @@ pub fn spawn_egg(
         .insert(dna)
         .insert(components::Relations::new(
             (entity, mind.color()),
-            parent_id,
+            parent.map(|(parent_id, _)| parent_id),
+        ))
+        .insert(parent.map_or_else(
+            || components::Lineage::founded_by(entity),
+            |(_, lineage)| lineage,
         ))
         .insert(mind)
         .insert(time::Age::default())
@@ -192,10 +223,17 @@ pub fn spawn_egg(
         .insert(body::HealthEfficiency::default())
         .insert(generation)
         .insert(BurntEnergy::new());
+    if let Some(rate) = dna.mutation_rate {
+        egg_entity.insert(attributes::MutationRate::new(rate));
+    }
 
     entity
 }
 
+pub fn move_spawners_system(time: Res<time::SimulationTime>, mut spawners: ResMut<Spawners>) {
+    spawners.update(time.elapsed_secs());
+}
+
 pub fn nearest_spawner_system(
     mut spawners: ResMut<Spawners>,
     organisms: Query<&Transform, With<Generation>>,
@@ -203,34 +241,18 @@ pub fn nearest_spawner_system(
 ) {
     let mut organism_counts = vec![0; spawners.len()];
     for position in organisms.iter() {
-        let distances: Vec<f32> = spawners
-            .iter()
-            .map(|s| s.distance(&position.translation))
-            .collect();
-        let index = distances
-            .iter()
-            .enumerate()
-            .min_by(|(_, a), (_, b)| a.total_cmp(b))
-            .map(|(index, _)| index)
-            .unwrap();
-        organism_counts[index] += 1;
+        if let Some(index) = spawners.nearest_bug_spawner(&position.translation) {
+            organism_counts[index] += 1;
+        }
     }
     for (i, spawner) in spawners.iter_mut().enumerate() {
         spawner.set_nearby_organisms(organism_counts[i]);
     }
     let mut food_counts = vec![0; spawners.len()];
     for (transform, plant) in plants.iter() {
-        let distances: Vec<f32> = spawners
-            .iter()
-            .map(|s| s.distance(&transform.translation))
-            .collect();
-        let index = distances
-            .iter()
-            .enumerate()
-            .min_by(|(_, a), (_, b)| a.total_cmp(b))
-            .map(|(index, _)| index)
-            .unwrap();
-        food_counts[index] += plant.energy().amount();
+        if let Some(index) = spawners.nearest_plant_spawner(&transform.translation) {
+            food_counts[index] += plant.energy().amount();
+        }
     }
     for (i, spawner) in spawners.iter_mut().enumerate() {
         spawner.set_nearby_food(food_counts[i]);
@@ -267,21 +289,28 @@ pub fn food_sprite_size(size: &Size) -> Vec2 {
     Vec2::splat(min_size)
 }
 
-fn spawn_plant(
+pub const fn plant_color(plant_config: &config::PlantConfig) -> Color {
+    let (r, g, b) = plant_config.tint;
+    Color::rgb(r, g, b)
+}
+
+pub fn spawn_plant(
     commands: &mut Commands,
-    asset_server: Res<AssetServer>,
+    asset_server: &Res<AssetServer>,
     energy: ecosystem::Energy,
     location: Vec3,
+    plant_type: components::PlantType,
 ) {
-    let food = components::plant_as_food(energy);
+    let plant_config = config::WorldConfig::global().plant_type(*plant_type);
+    let food = components::plant_as_food_from_config(energy, plant_config);
     let size = Size::new(food.size());
 
     commands
         .spawn(food_sprite_bundle(
-            &asset_server,
+            asset_server,
             &size,
             location,
-            Color::GREEN,
+            plant_color(plant_config),
         ))
         .insert(RigidBody::Dynamic)
         .insert(Damping {
@@ -289,14 +318,13 @@ fn spawn_plant(
             angular_damping: 1.0,
         })
         .insert(food_collider(&size))
-        .insert(ColliderMassProperties::Density(
-            config::WorldConfig::global().plant.density,
-        ))
+        .insert(ColliderMassProperties::Density(plant_config.density))
         .insert(Velocity::zero())
         .insert(ExternalImpulse::default())
         .insert(food)
         .insert(size)
-        .insert(components::Plant);
+        .insert(components::Plant)
+        .insert(plant_type);
 }
 
 pub fn spawn_meat(
@@ -304,18 +332,18 @@ pub fn spawn_meat(
     asset_server: &Res<AssetServer>,
     energy: ecosystem::Energy,
     location: Vec3,
+    source: Option<components::MeatSource>,
 ) {
     let food = components::meat_as_food(energy);
     let size = Size::new(food.size());
 
-    commands
-        .spawn(food_sprite_bundle(
-            asset_server,
-            &size,
-            location,
-            Color::MAROON,
-        ))
-        .insert(RigidBody::Dynamic)
+    let mut meat = commands.spawn(food_sprite_bundle(
+        asset_server,
+        &size,
+        location,
+        Color::MAROON,
+    ));
+    meat.insert(RigidBody::Dynamic)
         .insert(Damping {
             linear_damping: 1.0,
             angular_damping: 1.0,
@@ -329,40 +357,164 @@ pub fn spawn_meat(
         .insert(food)
         .insert(size)
         .insert(components::Meat);
+    if let Some(source) = source {
+        meat.insert(source);
+    }
 }
 
 #[derive(Resource)]
-pub struct PlantSizeRandomiser(Uniform<f32>);
+pub struct PlantSizeRandomiser(Vec<Uniform<f32>>);
 
 impl PlantSizeRandomiser {
-    pub fn new(bounds: (f32, f32)) -> Self {
-        Self(Uniform::new(bounds.0, bounds.1))
+    pub fn new(plant_types: &[config::PlantConfig]) -> Self {
+        Self(
+            plant_types
+                .iter()
+                .map(|p| Uniform::new(p.size_range.0, p.size_range.1))
+                .collect(),
+        )
     }
-    pub fn random_size(&self, rng: &mut rand::rngs::ThreadRng) -> f32 {
-        self.0.sample(rng)
+    /// A size for a new plant of this type, using the base plant's sizes for an unknown type.
+    pub fn random_size(&self, plant_type: usize, rng: &mut rand::rngs::ThreadRng) -> f32 {
+        self.0.get(plant_type).unwrap_or(&self.0[0]).sample(rng)
     }
 }
 
+pub fn plant_energy_floor() -> usize {
+    let config_instance = config::WorldConfig::global();
+    (config_instance.start_num * config_instance.start_energy).max(config_instance.energy_floor)
+}
+
 pub fn spawn_plant_system(
     mut commands: Commands,
     asset_server: Res<AssetServer>,
     mut ecosystem: ResMut<ecosystem::Ecosystem>,
-    spawners: Res<Spawners>,
+    mut ledger: ResMut<EnergyLedger>,
+    mut spawners: ResMut<Spawners>,
     plant_size_randomiser: Res<PlantSizeRandomiser>,
+    climate: Res<Climate>,
+    plant_query: Query<(), With<components::Plant>>,
 ) {
     let config_instance = config::WorldConfig::global();
-    let available_energy = ecosystem.available_energy().amount();
+    let seeding = &config_instance.seeding;
+    // When plants seed themselves spawners are only needed to keep a minimum population going.
+    if seeding.enabled && plant_query.iter().count() >= seeding.min_plants {
+        return;
+    }
+    let energy_floor = plant_energy_floor();
+
+    let mut rng = rand::thread_rng();
+    // Warmer seasons spawn more than one plant per frame on average, colder seasons fewer.
+    let spawn_rate = climate.plant_spawn_multiplier();
+    let mut plants_to_spawn = spawn_rate.trunc() as usize;
+    if rng.gen::<f32>() < spawn_rate.fract() {
+        plants_to_spawn += 1;
+    }
 
-    if available_energy
-        > (config_instance.start_num * config_instance.start_energy)
-            .max(config_instance.energy_floor)
-    {
-        let mut rng = rand::thread_rng();
-        let size = plant_size_randomiser.random_size(&mut rng);
+    for _ in 0..plants_to_spawn {
+        if ecosystem.available_energy().amount() <= energy_floor {
+            return;
+        }
+        let Some(spawner) = spawners.food_spawner() else { return };
+        let plant_type = spawner.random_plant_type(&mut rng);
+        let plant_config = config_instance.plant_type(plant_type);
+        let size = plant_size_randomiser.random_size(plant_type, &mut rng)
+            * climate.plant_size_multiplier();
         let Some(energy) =
-            ecosystem.request_energy(size as usize * config_instance.plant.energy_density) else {return};
-        let location = spawners.random_food_position(&mut rng);
-        spawn_plant(&mut commands, asset_server, energy, location);
+            ecosystem.request_energy(size as usize * plant_config.energy_density) else {return};
+        ledger.record(EnergyFlow::Planted, "spawn_plant_system", energy.amount());
+        let location = spawner.random_position(&mut rng);
+        spawn_plant(
+            &mut commands,
+            &asset_server,
+            energy,
+            location,
+            components::PlantType(plant_type),
+        );
+        spawner.record_plant_spawn();
+    }
+}
+
+type SeedingPlant<'a> = (&'a Transform, &'a Size, &'a components::PlantType);
+
+pub fn seed_plants_system(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    rapier_context: Res<RapierContext>,
+    mut ecosystem: ResMut<ecosystem::Ecosystem>,
+    mut ledger: ResMut<EnergyLedger>,
+    plant_size_randomiser: Res<PlantSizeRandomiser>,
+    plant_query: Query<SeedingPlant, With<components::Plant>>,
+) {
+    seed_plants(
+        &config::WorldConfig::global().seeding,
+        &mut commands,
+        &asset_server,
+        &rapier_context,
+        &mut ecosystem,
+        &mut ledger,
+        &plant_size_randomiser,
+        &plant_query,
+    );
+}
+
+/// Mature plants drop seeds nearby, unless the spot is already crowded with plants.
+pub fn seed_plants(
+    seeding: &config::SeedingConfig,
+    commands: &mut Commands,
+    asset_server: &Res<AssetServer>,
+    rapier_context: &RapierContext,
+    ecosystem: &mut ecosystem::Ecosystem,
+    ledger: &mut EnergyLedger,
+    plant_size_randomiser: &PlantSizeRandomiser,
+    plant_query: &Query<SeedingPlant, With<components::Plant>>,
+) {
+    let config_instance = config::WorldConfig::global();
+    if !seeding.enabled {
+        return;
+    }
+    let energy_floor = plant_energy_floor();
+    let crowding_area = Collider::ball(seeding.crowding_radius);
+    let mut rng = rand::thread_rng();
+
+    for (transform, size, plant_type) in plant_query.iter() {
+        let plant_config = config_instance.plant_type(**plant_type);
+        if **size < seeding.seed_size * plant_config.max_size
+            || rng.gen::<f32>() >= seeding.seed_probability
+        {
+            continue;
+        }
+        if ecosystem.available_energy().amount() <= energy_floor {
+            return;
+        }
+
+        let distance = rng.gen_range(0.0..seeding.dispersal_radius);
+        let angle = rng.gen_range(-PI..PI);
+        let (x, y) = polars_to_cart(distance, angle);
+        let location = transform.translation + Vec3::new(x, y, 0.0);
+
+        let mut neighbours = 0;
+        rapier_context.intersections_with_shape(
+            location.truncate(),
+            0.0,
+            &crowding_area,
+            QueryFilter::new(),
+            |entity| {
+                if plant_query.contains(entity) {
+                    neighbours += 1;
+                }
+                neighbours < seeding.max_neighbours
+            },
+        );
+        if neighbours >= seeding.max_neighbours {
+            continue;
+        }
+
+        let seed_size = plant_size_randomiser.random_size(**plant_type, &mut rng);
+        let seed_energy = seed_size as usize * plant_config.energy_density;
+        let Some(energy) = ecosystem.request_energy(seed_energy) else { continue };
+        ledger.record(EnergyFlow::Planted, "seed_plants_system", energy.amount());
+        spawn_plant(commands, asset_server, energy, location, *plant_type);
     }
 }
 
@@ -385,3 +537,136 @@ pub fn despawn_food_system(mut commands: Commands, food_query: Query<Entity, Wit
         commands.entity(entity).despawn_recursive();
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use bevy::{
+        app::App,
+        asset::AssetPlugin,
+        core::CorePlugin,
+        hierarchy::HierarchyPlugin,
+        prelude::{Entity, With},
+        time::TimePlugin,
+        transform::TransformPlugin,
+    };
+    use bevy_rapier2d::prelude::{NoUserData, RapierPhysicsPlugin};
+
+    use super::*;
+
+    fn spawn_mature_plant_system(
+        mut commands: Commands,
+        asset_server: Res<AssetServer>,
+        mut ecosystem: ResMut<ecosystem::Ecosystem>,
+    ) {
+        let energy = ecosystem.request_energy(10000).unwrap();
+        spawn_plant(
+            &mut commands,
+            &asset_server,
+            energy,
+            Vec3::ZERO,
+            components::PlantType(0),
+        );
+    }
+
+    #[test]
+    fn square_bugs_keep_their_shape() {
+        let size = Size::new(35.0);
+        let aspect_ratio = attributes::AspectRatio::new(1.0);
+
+        assert_eq!(bug_sprite_size(&size, &aspect_ratio), Vec2::splat(35.0));
+        let collider = bug_collider(&size, &aspect_ratio);
+        let capsule = collider.as_capsule().unwrap();
+        assert!((capsule.radius() - 35.0 / 3.5).abs() < 1e-5);
+        assert!((capsule.segment().a() - Vec2::new(0.0, -35.0 / 5.5)).length() < 1e-5);
+        assert!((capsule.segment().b() - Vec2::new(0.0, 35.0 / 5.5)).length() < 1e-5);
+    }
+
+    fn plant_count(app: &mut App) -> usize {
+        app.world
+            .query_filtered::<Entity, With<components::Plant>>()
+            .iter(&app.world)
+            .count()
+    }
+
+    #[test]
+    fn unknown_plant_types_take_the_base_plant_sizes() {
+        config::initialize_configs(None);
+        let plant_types = config::WorldConfig::global().plant_types();
+        let (lowest, highest) = plant_types[0].size_range;
+        let randomiser = PlantSizeRandomiser::new(plant_types);
+        let mut rng = rand::thread_rng();
+
+        for _ in 0..100 {
+            let size = randomiser.random_size(plant_types.len() + 3, &mut rng);
+            assert!((lowest..highest).contains(&size));
+        }
+    }
+
+    #[test]
+    fn seeding_stops_once_crowded() {
+        config::initialize_configs(None);
+        let config_instance = config::WorldConfig::global();
+        let seeding = config::SeedingConfig {
+            enabled: true,
+            seed_size: 0.0,
+            seed_probability: 1.0,
+            dispersal_radius: 20.0,
+            crowding_radius: 500.0,
+            max_neighbours: 3,
+            ..config::SeedingConfig::default()
+        };
+
+        let mut app = App::new();
+        app.add_plugin(CorePlugin::default())
+            .add_plugin(TimePlugin)
+            .add_plugin(TransformPlugin)
+            .add_plugin(HierarchyPlugin)
+            .add_plugin(AssetPlugin::default())
+            .add_plugin(RapierPhysicsPlugin::<NoUserData>::pixels_per_meter(1000.0))
+            .insert_resource(PlantSizeRandomiser::new(config_instance.plant_types()))
+            .insert_resource(ecosystem::Ecosystem::new(config_instance.world_energy))
+            .init_resource::<EnergyLedger>()
+            .add_startup_system(spawn_mature_plant_system)
+            .add_system(
+                move |mut commands: Commands,
+                      asset_server: Res<AssetServer>,
+                      rapier_context: Res<RapierContext>,
+                      mut ecosystem: ResMut<ecosystem::Ecosystem>,
+                      mut ledger: ResMut<EnergyLedger>,
+                      plant_size_randomiser: Res<PlantSizeRandomiser>,
+                      plant_query: Query<SeedingPlant, With<components::Plant>>| {
+                    seed_plants(
+                        &seeding,
+                        &mut commands,
+                        &asset_server,
+                        &rapier_context,
+                        &mut ecosystem,
+                        &mut ledger,
+                        &plant_size_randomiser,
+                        &plant_query,
+                    );
+                },
+            );
+
+        for _ in 0..10 {
+            app.update();
+        }
+        let seeded = plant_count(&mut app);
+        for _ in 0..10 {
+            app.update();
+        }
+
+        assert!(seeded > 1, "No seeds were planted.");
+        assert_eq!(
+            plant_count(&mut app),
+            seeded,
+            "Seeding continued once crowded."
+        );
+        assert!(
+            app.world
+                .resource::<EnergyLedger>()
+                .total(EnergyFlow::Planted)
+                > 0
+        );
+    }
+}
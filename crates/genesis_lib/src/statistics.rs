@@ -1,13 +1,18 @@
-use std::{collections::HashMap, fs, time::Duration};
+use std::{
+    collections::{BTreeMap, HashMap},
+    fs,
+    time::Duration,
+};
 
 use bevy::{
     app::AppExit,
-    prelude::{CoreStage, EventReader, Plugin, Query, Res, ResMut, Resource, SystemSet},
+    prelude::{CoreStage, EventReader, Plugin, Query, Res, ResMut, Resource, SystemSet, Without},
     time::Time,
 };
 use bevy_trait_query::ReadTraits;
 use components::{eat, lay, time};
 use derive_getters::Getters;
+use genesis_attributes as attributes;
 use genesis_components as components;
 use genesis_config::WorldConfig;
 use genesis_ecosystem as ecosystem;
@@ -15,7 +20,10 @@ use genesis_traits::AttributeDisplay;
 use iyes_loopless::prelude::*;
 use serde::{Deserialize, Serialize};
 
-use crate::{conditions, SimState};
+use crate::{
+    auditor::{EnergyFlow, EnergyLedger},
+    conditions, SimState,
+};
 
 fn last_element<T>(vector: &[T]) -> T
 where
@@ -65,6 +73,124 @@ impl EnergyStats {
     }
 }
 
+/// A global transfer of energy shown in the energy flux panel.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum EnergyFlux {
+    PlantGrowth,
+    PlantsEaten,
+    MeatEaten,
+    Digestion,
+    DigestionWaste,
+    Movement,
+    Thinking,
+    Eating,
+    Laying,
+    Growing,
+    Metabolism,
+    Grabbing,
+    Deaths,
+    Rotting,
+}
+
+impl EnergyFlux {
+    pub const ALL: [Self; 14] = [
+        Self::PlantGrowth,
+        Self::PlantsEaten,
+        Self::MeatEaten,
+        Self::Digestion,
+        Self::DigestionWaste,
+        Self::Movement,
+        Self::Thinking,
+        Self::Eating,
+        Self::Laying,
+        Self::Growing,
+        Self::Metabolism,
+        Self::Grabbing,
+        Self::Deaths,
+        Self::Rotting,
+    ];
+
+    /// The behaviour costs bugs pay back to the ecosystem.
+    pub const COSTS: [Self; 7] = [
+        Self::Movement,
+        Self::Thinking,
+        Self::Eating,
+        Self::Laying,
+        Self::Growing,
+        Self::Metabolism,
+        Self::Grabbing,
+    ];
+
+    pub const fn as_str(&self) -> &'static str {
+        match self {
+            Self::PlantGrowth => "Plant growth",
+            Self::PlantsEaten => "Plants eaten",
+            Self::MeatEaten => "Meat eaten",
+            Self::Digestion => "Digestion",
+            Self::DigestionWaste => "Digestion waste",
+            Self::Movement => "Movement",
+            Self::Thinking => "Thinking",
+            Self::Eating => "Eating",
+            Self::Laying => "Laying",
+            Self::Growing => "Growing",
+            Self::Metabolism => "Metabolism",
+            Self::Grabbing => "Grabbing",
+            Self::Deaths => "Deaths",
+            Self::Rotting => "Rotting",
+        }
+    }
+
+    fn ledger_total(self, ledger: &EnergyLedger) -> usize {
+        let spent = |tracker| ledger.part_total(EnergyFlow::Spent, tracker);
+        match self {
+            Self::PlantGrowth => ledger.total(EnergyFlow::Planted),
+            Self::PlantsEaten => ledger.part_total(EnergyFlow::Eaten, "plant"),
+            Self::MeatEaten => ledger.part_total(EnergyFlow::Eaten, "meat"),
+            Self::Digestion => ledger.total(EnergyFlow::Digested),
+            Self::DigestionWaste => ledger.total(EnergyFlow::Wasted),
+            Self::Movement => spent("TranslationSum") + spent("RotationSum"),
+            Self::Thinking => spent("ThinkingSum"),
+            Self::Eating => spent("EatingSum"),
+            Self::Laying => spent("LayingSum"),
+            Self::Growing => spent("GrowingSum"),
+            Self::Metabolism => spent("SizeSum"),
+            Self::Grabbing => spent("GrabbingSum"),
+            Self::Deaths => ledger.total(EnergyFlow::Killed),
+            Self::Rotting => ledger.total(EnergyFlow::Rotted),
+        }
+    }
+}
+
+/// Energy moved by each global flux during every stats interval.
+#[derive(Debug, Serialize, Deserialize, Default, Resource, Clone)]
+pub struct EnergyFluxStats {
+    flux: BTreeMap<EnergyFlux, Vec<usize>>,
+    #[serde(skip)]
+    last_totals: BTreeMap<EnergyFlux, usize>,
+}
+
+impl EnergyFluxStats {
+    pub fn series(&self, flux: EnergyFlux) -> &[usize] {
+        self.flux.get(&flux).map_or(&[], Vec::as_slice)
+    }
+
+    pub fn current(&self, flux: EnergyFlux) -> usize {
+        last_element(self.series(flux))
+    }
+
+    fn update(&mut self, ledger: &EnergyLedger) {
+        for flux in EnergyFlux::ALL {
+            let total = flux.ledger_total(ledger);
+            let last_total = self.last_totals.insert(flux, total).unwrap_or_default();
+            // The ledger starts again from zero when a simulation is loaded.
+            self.flux
+                .entry(flux)
+                .or_default()
+                .push(total.saturating_sub(last_total));
+        }
+    }
+}
+
 #[derive(Debug, Getters, Serialize, Deserialize, Default, Resource, Clone)]
 pub struct BugPerformance {
     highest_energy_consumed: Vec<usize>,
@@ -118,6 +244,10 @@ pub fn energy_stats_system(
     stats.food_energy.push(total_food);
 }
 
+pub fn energy_flux_stats_system(mut stats: ResMut<EnergyFluxStats>, ledger: Res<EnergyLedger>) {
+    stats.update(&ledger);
+}
+
 pub fn performance_stats_system(
     mut stats: ResMut<BugPerformance>,
     performance_query: Query<(
@@ -145,6 +275,178 @@ pub fn performance_stats_system(
     stats.oldest_bug.push(oldest_bug);
 }
 
+/// Kills credited to the generation of the attacker, and the ratio of predators (bugs
+/// preferring meat) to prey sampled each stats tick.
+#[derive(Debug, Getters, Serialize, Deserialize, Default, Resource, Clone)]
+pub struct CombatStats {
+    kills_by_generation: BTreeMap<usize, usize>,
+    predator_prey_ratio: Vec<f32>,
+}
+
+impl CombatStats {
+    pub fn record_kill(&mut self, attacker_generation: usize) {
+        *self
+            .kills_by_generation
+            .entry(attacker_generation)
+            .or_default() += 1;
+    }
+
+    pub fn current_predator_prey_ratio(&self) -> f32 {
+        last_element(&self.predator_prey_ratio)
+    }
+}
+
+pub fn combat_stats_system(
+    mut stats: ResMut<CombatStats>,
+    bug_query: Query<&attributes::FoodPreference, Without<components::Egg>>,
+) {
+    let (predators, prey) = bug_query
+        .iter()
+        .partition::<Vec<_>, _>(|preference| ***preference > 0.5);
+    let ratio = predators.len() as f32 / prey.len().max(1) as f32;
+    stats.predator_prey_ratio.push(ratio);
+}
+
+/// What a bug took a bite of.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Meal {
+    Plant,
+    /// Meat, with the lineage it came from if known and whether it was the eater's kin.
+    Meat {
+        lineage: Option<components::Lineage>,
+        kin: bool,
+    },
+}
+
+/// Energy one lineage has eaten, by what it ate.
+#[derive(Debug, Getters, Serialize, Deserialize, Default, Clone, PartialEq, Eq)]
+pub struct FoodWebRow {
+    plant: usize,
+    meat: BTreeMap<u32, usize>,
+    unknown_meat: usize,
+    kin: usize,
+}
+
+impl FoodWebRow {
+    pub fn meat_total(&self) -> usize {
+        self.meat.values().sum::<usize>() + self.unknown_meat
+    }
+}
+
+/// Energy eaten by each lineage, split into plants and meat from each lineage preyed upon.
+/// Meat from the eater's own lineage is cannibalism, and `kin` is the part of that which came
+/// from a parent, child or sibling.
+#[derive(Debug, Getters, Serialize, Deserialize, Default, Resource, Clone)]
+pub struct FoodWeb {
+    eaters: BTreeMap<u32, FoodWebRow>,
+}
+
+impl FoodWeb {
+    pub fn record(&mut self, eater: components::Lineage, meal: Meal, energy: usize) {
+        if energy == 0 {
+            return;
+        }
+        let row = self.eaters.entry(*eater).or_default();
+        match meal {
+            Meal::Plant => row.plant += energy,
+            Meal::Meat { lineage, kin } => {
+                match lineage {
+                    Some(lineage) => *row.meat.entry(*lineage).or_default() += energy,
+                    None => row.unknown_meat += energy,
+                }
+                if kin {
+                    row.kin += energy;
+                }
+            }
+        }
+    }
+
+    pub fn plant_total(&self) -> usize {
+        self.eaters.values().map(|row| row.plant).sum()
+    }
+
+    pub fn meat_total(&self) -> usize {
+        self.eaters.values().map(FoodWebRow::meat_total).sum()
+    }
+
+    pub fn cannibalism_total(&self) -> usize {
+        self.eaters
+            .iter()
+            .filter_map(|(eater, row)| row.meat.get(eater))
+            .sum()
+    }
+
+    pub fn kin_total(&self) -> usize {
+        self.eaters.values().map(|row| row.kin).sum()
+    }
+}
+
+/// Spread of an attribute across the population at one moment.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct Quantiles {
+    pub min: f32,
+    pub lower_quartile: f32,
+    pub median: f32,
+    pub upper_quartile: f32,
+    pub max: f32,
+}
+
+impl Quantiles {
+    pub fn from_values(values: &mut [f32]) -> Option<Self> {
+        if values.is_empty() {
+            return None;
+        }
+        values.sort_by(f32::total_cmp);
+        let at = |q: f32| values[((values.len() - 1) as f32 * q).round() as usize];
+        Some(Self {
+            min: at(0.0),
+            lower_quartile: at(0.25),
+            median: at(0.5),
+            upper_quartile: at(0.75),
+            max: at(1.0),
+        })
+    }
+}
+
+/// Quantiles of every attribute across living bugs, sampled each stats tick while bugs are alive.
+#[derive(Debug, Serialize, Deserialize, Default, Resource, Clone)]
+pub struct AttributeStats {
+    history: BTreeMap<String, Vec<Quantiles>>,
+}
+
+impl AttributeStats {
+    pub fn history(&self, name: &str) -> &[Quantiles] {
+        self.history.get(name).map_or(&[], Vec::as_slice)
+    }
+}
+
+pub fn attribute_stats_system(
+    mut stats: ResMut<AttributeStats>,
+    bug_query: Query<(&dyn AttributeDisplay, &attributes::TradeOffs), Without<components::Egg>>,
+) {
+    let mut values: BTreeMap<&str, Vec<f32>> = BTreeMap::new();
+    for (attrs, trade_offs) in bug_query.iter() {
+        for attr in attrs.iter() {
+            values.entry(attr.name()).or_default().push(attr.value());
+        }
+        for trade_off in trade_offs.iter() {
+            values
+                .entry(trade_off.name())
+                .or_default()
+                .push(trade_off.value());
+        }
+    }
+    for (name, mut attribute_values) in values {
+        if let Some(quantiles) = Quantiles::from_values(&mut attribute_values) {
+            stats
+                .history
+                .entry(name.to_string())
+                .or_default()
+                .push(quantiles);
+        }
+    }
+}
+
 #[derive(Serialize, Deserialize, Debug, Clone)]
 struct BugData {
     relations: components::Relations,
@@ -191,6 +493,18 @@ impl FamilyTree {
             self.dead_relations.push(bug_data);
         }
     }
+
+    /// Looks up a dead bug by its relations id, returning its relations and attributes.
+    pub fn dead_relative(
+        &self,
+        id: u32,
+    ) -> Option<(&components::Relations, &HashMap<String, f32>)> {
+        self.dead_relations
+            .iter()
+            .rev()
+            .find(|bug_data| bug_data.relations.entity().0 == id)
+            .map(|bug_data| (&bug_data.relations, &bug_data.attributes))
+    }
 }
 
 pub fn family_tree_update(
@@ -208,7 +522,11 @@ struct RunInfo<'a> {
     run_config: &'a WorldConfig,
     count_stats: &'a CountStats,
     energy_stats: &'a EnergyStats,
+    energy_flux_stats: &'a EnergyFluxStats,
     performance_stats: &'a BugPerformance,
+    attribute_stats: &'a AttributeStats,
+    combat_stats: &'a CombatStats,
+    food_web: &'a FoodWeb,
     family_tree: &'a FamilyTree,
 }
 
@@ -218,7 +536,11 @@ impl<'a> RunInfo<'a> {
         run_config: &'a WorldConfig,
         count_stats: &'a CountStats,
         energy_stats: &'a EnergyStats,
+        energy_flux_stats: &'a EnergyFluxStats,
         performance_stats: &'a BugPerformance,
+        attribute_stats: &'a AttributeStats,
+        combat_stats: &'a CombatStats,
+        food_web: &'a FoodWeb,
         family_tree: &'a FamilyTree,
     ) -> Self {
         Self {
@@ -226,7 +548,11 @@ impl<'a> RunInfo<'a> {
             run_config,
             count_stats,
             energy_stats,
+            energy_flux_stats,
             performance_stats,
+            attribute_stats,
+            combat_stats,
+            food_web,
             family_tree,
         }
     }
@@ -240,7 +566,11 @@ pub fn save_stats(
     time: Res<Time>,
     count_stats: Res<CountStats>,
     energy_stats: Res<EnergyStats>,
+    energy_flux_stats: Res<EnergyFluxStats>,
     performance_stats: Res<BugPerformance>,
+    attribute_stats: Res<AttributeStats>,
+    combat_stats: Res<CombatStats>,
+    food_web: Res<FoodWeb>,
     family_tree: Res<FamilyTree>,
 ) {
     if !exit_event.is_empty() || !save_stats.is_empty() {
@@ -250,7 +580,11 @@ pub fn save_stats(
             WorldConfig::global(),
             &count_stats,
             &energy_stats,
+            &energy_flux_stats,
             &performance_stats,
+            &attribute_stats,
+            &combat_stats,
+            &food_web,
             &family_tree,
         );
         let j = serde_json::to_string_pretty(&run_info).unwrap();
@@ -264,6 +598,9 @@ pub fn global_statistics_system_set() -> SystemSet {
         .run_if_not(conditions::is_paused)
         .with_system(count_system)
         .with_system(energy_stats_system)
+        .with_system(energy_flux_stats_system)
+        .with_system(attribute_stats_system)
+        .with_system(combat_stats_system)
         .with_system(performance_stats_system)
         .into()
 }
@@ -286,3 +623,86 @@ impl Plugin for GenesisStatsPlugin {
             .add_system_set_to_stage(CoreStage::Last, save_stats_system_set());
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn energy_flux_records_interval_transfers() {
+        let mut ledger = EnergyLedger::default();
+        let mut stats = EnergyFluxStats::default();
+
+        ledger.record_part(EnergyFlow::Eaten, "eating_system", "plant", 10);
+        ledger.record_part(EnergyFlow::Spent, "energy_return_system", "RotationSum", 2);
+        stats.update(&ledger);
+        ledger.record_part(EnergyFlow::Eaten, "eating_system", "plant", 5);
+        ledger.record_part(
+            EnergyFlow::Spent,
+            "energy_return_system",
+            "TranslationSum",
+            3,
+        );
+        stats.update(&ledger);
+
+        assert_eq!(stats.series(EnergyFlux::PlantsEaten), &[10, 5]);
+        assert_eq!(stats.series(EnergyFlux::Movement), &[2, 3]);
+        assert_eq!(stats.current(EnergyFlux::MeatEaten), 0);
+    }
+
+    #[test]
+    fn food_web_separates_plants_prey_and_kin() {
+        let mut food_web = FoodWeb::default();
+        let (wolves, sheep) = (components::Lineage(1), components::Lineage(2));
+
+        food_web.record(sheep, Meal::Plant, 10);
+        food_web.record(
+            wolves,
+            Meal::Meat {
+                lineage: Some(sheep),
+                kin: false,
+            },
+            6,
+        );
+        food_web.record(
+            wolves,
+            Meal::Meat {
+                lineage: Some(wolves),
+                kin: true,
+            },
+            3,
+        );
+        food_web.record(
+            wolves,
+            Meal::Meat {
+                lineage: None,
+                kin: false,
+            },
+            2,
+        );
+        food_web.record(sheep, Meal::Plant, 0);
+
+        assert_eq!(food_web.plant_total(), 10);
+        assert_eq!(food_web.meat_total(), 11);
+        assert_eq!(food_web.cannibalism_total(), 3);
+        assert_eq!(food_web.kin_total(), 3);
+        assert_eq!(food_web.eaters()[&1].meat()[&2], 6);
+    }
+
+    #[test]
+    fn quantiles_of_population() {
+        assert_eq!(Quantiles::from_values(&mut []), None);
+
+        let quantiles = Quantiles::from_values(&mut [5.0, 1.0, 3.0, 2.0, 4.0]).unwrap();
+        assert_eq!(
+            quantiles,
+            Quantiles {
+                min: 1.0,
+                lower_quartile: 2.0,
+                median: 3.0,
+                upper_quartile: 4.0,
+                max: 5.0,
+            }
+        );
+    }
+}
@@ -1,38 +1,24 @@
 use bevy_egui::egui;
 use genesis_components::mind::*;
+use genesis_config as config;
 
-pub(super) type BugBrainInfo<'a> = (&'a MindInput, &'a MindLayout, &'a MindOutput);
-
-const NEURON_NAMES: [&str; 28] = [
-    "Constant",
-    "Movement",
-    "Rotation",
-    "Energy",
-    "Health",
-    "Age",
-    "Visible bugs",
-    "Bug angle",
-    "Bug distance",
-    "Bug species",
-    "Visible plants",
-    "Plant angle",
-    "Plant distance",
-    "Visible meat",
-    "Meat angle",
-    "Meat distance",
-    "Heartbeat",
-    "Internal timer",
-    "Fullness",
-    "Movement",
-    "Rotation",
-    "Reproduce",
-    "Eat",
-    "Reset timer",
-    "Want to grow",
-    "Want to grab",
-    "Digest",
-    "Attack",
-];
+use crate::recorder::ActivationRecorder;
+
+pub(super) type BugBrainInfo<'a> = (
+    &'a MindInput,
+    &'a MindLayout,
+    &'a MindOutput,
+    Option<&'a MindMutations>,
+);
+
+const MUTATION_COLOR: egui::Color32 = egui::Color32::YELLOW;
+const TRACE_SIZE: egui::Vec2 = egui::Vec2::new(300.0, 24.0);
+
+pub fn neuron_name(index: usize) -> String {
+    config::NEURON_NAMES
+        .get(index)
+        .map_or_else(|| format!("Hidden {index}"), |name| (*name).to_string())
+}
 
 fn paint_synapses(ui: &mut egui::Ui, synapses: &[PaintedSynapse]) {
     for syn in synapses {
@@ -41,6 +27,46 @@ fn paint_synapses(ui: &mut egui::Ui, synapses: &[PaintedSynapse]) {
     }
 }
 
+/// Outlines the neurons and synapses that changed when this mind was mutated from its parent's.
+fn paint_mutations(ui: &egui::Ui, neuron_layout: &[GuiNeuron], mutations: &MindMutations) {
+    let position = |index: usize| neuron_layout.get(index).and_then(|neuron| neuron.pos);
+    let stroke = egui::Stroke::new(9.0, MUTATION_COLOR);
+    for change in mutations.changes() {
+        if let Some((Some(start), Some(end))) = change
+            .synapse()
+            .map(|(from, to)| (position(from), position(to)))
+        {
+            ui.painter().line_segment([start, end], stroke);
+        }
+    }
+    for gui_neuron in neuron_layout {
+        let Some(neuron_position) = gui_neuron.pos else {
+            continue;
+        };
+        if mutations.touches_neuron(gui_neuron.index) {
+            ui.painter().circle_stroke(
+                neuron_position,
+                RADIUS + 3.0,
+                egui::Stroke::new(3.0, MUTATION_COLOR),
+            );
+        }
+    }
+}
+
+fn mutation_list(ui: &mut egui::Ui, mutations: Option<&MindMutations>) {
+    let Some(mutations) = mutations else {
+        return;
+    };
+    ui.collapsing(
+        format!("Mutations since parent ({})", mutations.changes().len()),
+        |ui| {
+            for change in mutations.changes() {
+                ui.colored_label(MUTATION_COLOR, change.to_string());
+            }
+        },
+    );
+}
+
 fn paint_neuron_values(
     ui: &mut egui::Ui,
     neuron_index: usize,
@@ -67,7 +93,7 @@ fn paint_neuron_labels(ui: &mut egui::Ui, response: &egui::Response, neuron: &Gu
     if dist >= RADIUS {
         return;
     }
-    let label = NEURON_NAMES
+    let label = config::NEURON_NAMES
         .get(neuron.index)
         .map_or(neuron.activation.as_str(), |l| *l);
 
@@ -100,7 +126,7 @@ fn paint_neurons(
 }
 
 pub(super) fn bug_brain_sub_panel(ui: &mut egui::Ui, brain_info: &BugBrainInfo) {
-    let (mind_in, mind_layout, mind_out) = brain_info;
+    let (mind_in, mind_layout, mind_out, mutations) = brain_info;
 
     let mut mind_values: Vec<f32> = mind_in.iter().copied().collect();
     mind_values.extend(&mind_out.0);
@@ -108,6 +134,56 @@ pub(super) fn bug_brain_sub_panel(ui: &mut egui::Ui, brain_info: &BugBrainInfo)
     let (_rect, response) =
         ui.allocate_exact_size(egui::Vec2::new(1400.0, 680.0), egui::Sense::hover());
 
+    if let Some(mutations) = mutations {
+        paint_mutations(ui, mind_layout.neurons(), mutations);
+    }
     paint_synapses(ui, mind_layout.synapses());
     paint_neurons(ui, &response, mind_layout.neurons(), &mind_values);
+    mutation_list(ui, *mutations);
+}
+
+fn paint_trace(ui: &mut egui::Ui, values: &[f32]) {
+    let (rect, _response) = ui.allocate_exact_size(TRACE_SIZE, egui::Sense::hover());
+    ui.painter()
+        .rect_filled(rect, 0.0, egui::Color32::from_gray(30));
+    let min = values.iter().copied().fold(f32::INFINITY, f32::min);
+    let max = values.iter().copied().fold(f32::NEG_INFINITY, f32::max);
+    let range = (max - min).max(f32::EPSILON);
+    let step = rect.width() / values.len().max(2).saturating_sub(1) as f32;
+    let points = values
+        .iter()
+        .enumerate()
+        .map(|(i, value)| {
+            egui::pos2(
+                (i as f32).mul_add(step, rect.left()),
+                ((value - min) / range).mul_add(-rect.height(), rect.bottom()),
+            )
+        })
+        .collect();
+    ui.painter().add(egui::Shape::line(
+        points,
+        egui::Stroke::new(1.5, egui::Color32::WHITE),
+    ));
+}
+
+/// Scrolling traces of every neuron's recorded activation. Returns true if export was clicked.
+pub(super) fn activation_traces(ui: &mut egui::Ui, recorder: &ActivationRecorder) -> bool {
+    let mut export = false;
+    ui.collapsing("Activation traces", |ui| {
+        export = ui.button("Export CSV").clicked();
+        egui::ScrollArea::vertical()
+            .max_height(300.0)
+            .show(ui, |ui| {
+                egui::Grid::new("activation_traces").show(ui, |ui| {
+                    for neuron in 0..recorder.neuron_count() {
+                        let trace = recorder.trace(neuron);
+                        ui.label(neuron_name(neuron));
+                        paint_trace(ui, &trace);
+                        ui.label(format!("{:.2}", trace.last().copied().unwrap_or_default()));
+                        ui.end_row();
+                    }
+                });
+            });
+    });
+    export
 }
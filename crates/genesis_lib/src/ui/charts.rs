@@ -0,0 +1,160 @@
+use std::fmt::Write;
+
+use bevy_egui::egui::{
+    self,
+    plot::{Legend, Line, Plot, PlotPoints},
+};
+
+/// How much history a chart shows. Statistics are sampled once per simulated second.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum TimeWindow {
+    Minute,
+    TenMinutes,
+    Hour,
+    #[default]
+    All,
+}
+
+impl TimeWindow {
+    const ALL: [Self; 4] = [Self::Minute, Self::TenMinutes, Self::Hour, Self::All];
+
+    const fn as_str(self) -> &'static str {
+        match self {
+            Self::Minute => "Last minute",
+            Self::TenMinutes => "Last 10 minutes",
+            Self::Hour => "Last hour",
+            Self::All => "All",
+        }
+    }
+
+    const fn samples(self) -> Option<usize> {
+        match self {
+            Self::Minute => Some(60),
+            Self::TenMinutes => Some(600),
+            Self::Hour => Some(3600),
+            Self::All => None,
+        }
+    }
+}
+
+/// Time windows for each of the charts in the global panel.
+#[derive(Debug, Default)]
+pub struct ChartWindows {
+    pub population: TimeWindow,
+    pub energy: TimeWindow,
+    pub performance: TimeWindow,
+    pub energy_flux: TimeWindow,
+    pub attributes: TimeWindow,
+}
+
+pub struct ChartSeries {
+    name: &'static str,
+    values: Vec<f64>,
+}
+
+impl ChartSeries {
+    pub fn from_usize(name: &'static str, values: &[usize]) -> Self {
+        Self {
+            name,
+            values: values.iter().map(|value| *value as f64).collect(),
+        }
+    }
+
+    pub fn from_f32(name: &'static str, values: &[f32]) -> Self {
+        Self {
+            name,
+            values: values.iter().map(|value| f64::from(*value)).collect(),
+        }
+    }
+}
+
+fn visible_range(series: &[ChartSeries], window: TimeWindow) -> std::ops::Range<usize> {
+    let len = series
+        .iter()
+        .map(|series| series.values.len())
+        .max()
+        .unwrap_or_default();
+    let start = window
+        .samples()
+        .map_or(0, |samples| len.saturating_sub(samples));
+    start..len
+}
+
+fn to_csv(series: &[ChartSeries], range: std::ops::Range<usize>) -> String {
+    let mut csv = String::from("time");
+    for series in series {
+        csv.push(',');
+        csv.push_str(series.name);
+    }
+    csv.push('\n');
+    for i in range {
+        let _ = write!(csv, "{i}");
+        for series in series {
+            csv.push(',');
+            if let Some(value) = series.values.get(i) {
+                let _ = write!(csv, "{value}");
+            }
+        }
+        csv.push('\n');
+    }
+    csv
+}
+
+/// An overlaid line chart of several statistics, with a time window selector and CSV copy.
+pub fn time_series_chart(
+    ui: &mut egui::Ui,
+    id: &str,
+    window: &mut TimeWindow,
+    series: &[ChartSeries],
+) {
+    let previous_window = *window;
+    let range = visible_range(series, *window);
+    ui.horizontal(|ui| {
+        egui::ComboBox::from_id_source(id)
+            .selected_text(window.as_str())
+            .show_ui(ui, |ui| {
+                for option in TimeWindow::ALL {
+                    ui.selectable_value(window, option, option.as_str());
+                }
+            });
+        if ui.button("Copy CSV").clicked() {
+            ui.output().copied_text = to_csv(series, range.clone());
+        }
+    });
+
+    let mut plot = Plot::new(id).height(180.0).legend(Legend::default());
+    if *window != previous_window {
+        plot = plot.reset();
+    }
+    plot.show(ui, |plot_ui| {
+        for series in series {
+            let points: PlotPoints = range
+                .clone()
+                .filter_map(|i| series.values.get(i).map(|value| [i as f64, *value]))
+                .collect();
+            plot_ui.line(Line::new(points).name(series.name));
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn csv_covers_the_time_window() {
+        let series = [
+            ChartSeries::from_usize("adults", &[1, 2, 3]),
+            ChartSeries::from_f32("age", &[0.5, 1.5]),
+        ];
+        let range = visible_range(&series, TimeWindow::All);
+        assert_eq!(range, 0..3);
+        assert_eq!(
+            to_csv(&series, range),
+            "time,adults,age\n0,1,0.5\n1,2,1.5\n2,3,\n"
+        );
+
+        let series = [ChartSeries::from_usize("eggs", &[0; 100])];
+        assert_eq!(visible_range(&series, TimeWindow::Minute), 40..100);
+    }
+}
@@ -0,0 +1,208 @@
+use bevy_egui::egui;
+
+use crate::{
+    statistics::{EnergyFlux, EnergyFluxStats},
+    ui::charts::{self, ChartSeries, TimeWindow},
+};
+
+const NODE_SIZE: egui::Vec2 = egui::Vec2::new(80.0, 26.0);
+const ARROW_HEAD: f32 = 8.0;
+const MAX_STROKE: f32 = 8.0;
+const FLOW_COLOR: egui::Color32 = egui::Color32::from_rgb(230, 180, 60);
+
+#[derive(Debug, Clone, Copy)]
+enum Node {
+    Plants,
+    Stomachs,
+    Bugs,
+    Ecosystem,
+    Meat,
+}
+
+impl Node {
+    const ALL: [Self; 5] = [
+        Self::Plants,
+        Self::Stomachs,
+        Self::Bugs,
+        Self::Ecosystem,
+        Self::Meat,
+    ];
+
+    const fn as_str(self) -> &'static str {
+        match self {
+            Self::Plants => "Plants",
+            Self::Stomachs => "Stomachs",
+            Self::Bugs => "Bugs",
+            Self::Ecosystem => "Ecosystem",
+            Self::Meat => "Meat",
+        }
+    }
+
+    fn position(self, origin: egui::Pos2) -> egui::Pos2 {
+        let offset = match self {
+            Self::Plants => egui::vec2(60.0, 30.0),
+            Self::Stomachs => egui::vec2(240.0, 30.0),
+            Self::Bugs => egui::vec2(420.0, 30.0),
+            Self::Ecosystem => egui::vec2(60.0, 190.0),
+            Self::Meat => egui::vec2(240.0, 190.0),
+        };
+        origin + offset
+    }
+}
+
+struct Flow {
+    label: &'static str,
+    amount: usize,
+    path: Vec<egui::Pos2>,
+}
+
+fn flows(origin: egui::Pos2, flux_stats: &EnergyFluxStats) -> Vec<Flow> {
+    let at = |node: Node| node.position(origin);
+    let costs = EnergyFlux::COSTS
+        .iter()
+        .map(|flux| flux_stats.current(*flux))
+        .sum();
+    vec![
+        Flow {
+            label: EnergyFlux::PlantGrowth.as_str(),
+            amount: flux_stats.current(EnergyFlux::PlantGrowth),
+            path: vec![at(Node::Ecosystem), at(Node::Plants)],
+        },
+        Flow {
+            label: EnergyFlux::PlantsEaten.as_str(),
+            amount: flux_stats.current(EnergyFlux::PlantsEaten),
+            path: vec![at(Node::Plants), at(Node::Stomachs)],
+        },
+        Flow {
+            label: EnergyFlux::MeatEaten.as_str(),
+            amount: flux_stats.current(EnergyFlux::MeatEaten),
+            path: vec![at(Node::Meat), at(Node::Stomachs)],
+        },
+        Flow {
+            label: EnergyFlux::Digestion.as_str(),
+            amount: flux_stats.current(EnergyFlux::Digestion),
+            path: vec![at(Node::Stomachs), at(Node::Bugs)],
+        },
+        Flow {
+            label: EnergyFlux::DigestionWaste.as_str(),
+            amount: flux_stats.current(EnergyFlux::DigestionWaste),
+            path: vec![at(Node::Stomachs), at(Node::Ecosystem)],
+        },
+        Flow {
+            label: EnergyFlux::Deaths.as_str(),
+            amount: flux_stats.current(EnergyFlux::Deaths),
+            path: vec![at(Node::Bugs), at(Node::Meat)],
+        },
+        Flow {
+            label: EnergyFlux::Rotting.as_str(),
+            amount: flux_stats.current(EnergyFlux::Rotting),
+            path: vec![at(Node::Meat), at(Node::Ecosystem)],
+        },
+        // Routed underneath the other nodes so it doesn't cross the meat arrows.
+        Flow {
+            label: "Costs",
+            amount: costs,
+            path: vec![
+                at(Node::Bugs),
+                at(Node::Bugs) + egui::vec2(0.0, 220.0),
+                at(Node::Ecosystem) + egui::vec2(0.0, 60.0),
+                at(Node::Ecosystem),
+            ],
+        },
+    ]
+}
+
+/// Moves a point back from the centre of a node to its edge along a direction.
+fn node_edge(centre: egui::Pos2, direction: egui::Vec2) -> egui::Pos2 {
+    let half = NODE_SIZE / 2.0;
+    let scale = (half.x / direction.x.abs()).min(half.y / direction.y.abs());
+    centre + direction * scale
+}
+
+fn paint_flow(painter: &egui::Painter, flow: &Flow, max_amount: usize) {
+    let width = 1.0 + (MAX_STROKE - 1.0) * flow.amount as f32 / max_amount.max(1) as f32;
+    let stroke = egui::Stroke::new(width, FLOW_COLOR);
+
+    let mut path = flow.path.clone();
+    let last = path.len() - 1;
+    path[0] = node_edge(path[0], (path[1] - path[0]).normalized());
+    path[last] = node_edge(path[last], (path[last - 1] - path[last]).normalized());
+    for segment in path.windows(2) {
+        painter.line_segment([segment[0], segment[1]], stroke);
+    }
+
+    let tip = path[last];
+    let direction = (tip - path[last - 1]).normalized();
+    let back = tip - direction * ARROW_HEAD;
+    let side = direction.rot90() * ARROW_HEAD / 2.0;
+    painter.line_segment([tip, back + side], stroke);
+    painter.line_segment([tip, back - side], stroke);
+
+    let middle = path.len() / 2;
+    let label_position = path[middle - 1] + (path[middle] - path[middle - 1]) / 2.0;
+    painter.text(
+        label_position,
+        egui::Align2::CENTER_BOTTOM,
+        format!("{}: {}", flow.label, flow.amount),
+        egui::FontId::proportional(12.0),
+        egui::Color32::WHITE,
+    );
+}
+
+fn paint_nodes(painter: &egui::Painter, origin: egui::Pos2) {
+    for node in Node::ALL {
+        let rect = egui::Rect::from_center_size(node.position(origin), NODE_SIZE);
+        painter.rect_filled(rect, 4.0, egui::Color32::DARK_GRAY);
+        painter.text(
+            rect.center(),
+            egui::Align2::CENTER_CENTER,
+            node.as_str(),
+            egui::FontId::proportional(14.0),
+            egui::Color32::WHITE,
+        );
+    }
+}
+
+fn flux_diagram(ui: &mut egui::Ui, flux_stats: &EnergyFluxStats) {
+    let (rect, _response) =
+        ui.allocate_exact_size(egui::Vec2::new(480.0, 270.0), egui::Sense::hover());
+    let painter = ui.painter_at(rect);
+    let flows = flows(rect.min, flux_stats);
+    let max_amount = flows
+        .iter()
+        .map(|flow| flow.amount)
+        .max()
+        .unwrap_or_default();
+
+    for flow in &flows {
+        paint_flow(&painter, flow, max_amount);
+    }
+    paint_nodes(&painter, rect.min);
+}
+
+fn cost_breakdown(ui: &mut egui::Ui, flux_stats: &EnergyFluxStats) {
+    ui.collapsing("Costs", |ui| {
+        for flux in EnergyFlux::COSTS {
+            ui.label(format!("{}: {}", flux.as_str(), flux_stats.current(flux)));
+        }
+    });
+}
+
+fn flux_chart(ui: &mut egui::Ui, window: &mut TimeWindow, flux_stats: &EnergyFluxStats) {
+    let series: Vec<ChartSeries> = EnergyFlux::ALL
+        .iter()
+        .map(|flux| ChartSeries::from_usize(flux.as_str(), flux_stats.series(*flux)))
+        .collect();
+    charts::time_series_chart(ui, "energy_flux_chart", window, &series);
+}
+
+pub(super) fn energy_flux_sub_panel(
+    ui: &mut egui::Ui,
+    window: &mut TimeWindow,
+    flux_stats: &EnergyFluxStats,
+) {
+    ui.label("Energy moved during the last stats interval");
+    flux_diagram(ui, flux_stats);
+    cost_breakdown(ui, flux_stats);
+    flux_chart(ui, window, flux_stats);
+}
@@ -0,0 +1,99 @@
+use std::collections::BTreeMap;
+
+use bevy_egui::egui;
+
+use crate::statistics::{FoodWeb, FoodWebRow};
+
+/// Lineages shown as rows and as prey columns; the rest are summed into "Other meat".
+const MAX_LINEAGES: usize = 10;
+const CANNIBALISM_COLOR: egui::Color32 = egui::Color32::from_rgb(220, 90, 90);
+
+fn top_eaters(food_web: &FoodWeb) -> Vec<(&u32, &FoodWebRow)> {
+    let mut eaters: Vec<_> = food_web.eaters().iter().collect();
+    eaters.sort_by_key(|(_, row)| std::cmp::Reverse(row.plant() + row.meat_total()));
+    eaters.truncate(MAX_LINEAGES);
+    eaters
+}
+
+fn top_prey(food_web: &FoodWeb) -> Vec<u32> {
+    let mut eaten: BTreeMap<u32, usize> = BTreeMap::new();
+    for row in food_web.eaters().values() {
+        for (prey, energy) in row.meat() {
+            *eaten.entry(*prey).or_default() += energy;
+        }
+    }
+    let mut prey: Vec<_> = eaten.into_iter().collect();
+    prey.sort_by_key(|(_, energy)| std::cmp::Reverse(*energy));
+    prey.into_iter()
+        .take(MAX_LINEAGES)
+        .map(|(lineage, _)| lineage)
+        .collect()
+}
+
+fn energy_label(energy: usize) -> String {
+    if energy == 0 {
+        String::new()
+    } else {
+        energy.to_string()
+    }
+}
+
+fn totals(ui: &mut egui::Ui, food_web: &FoodWeb) {
+    ui.label(format!("Plant eaten: {}", food_web.plant_total()));
+    ui.label(format!("Meat eaten: {}", food_web.meat_total()));
+    ui.label(format!(
+        "Cannibalism: {} ({} from kin)",
+        food_web.cannibalism_total(),
+        food_web.kin_total()
+    ));
+}
+
+fn grid_header(ui: &mut egui::Ui, prey: &[u32]) {
+    ui.label("Eater \\ Food");
+    ui.label("Plant");
+    for lineage in prey {
+        ui.label(format!("Lineage {lineage}"));
+    }
+    ui.label("Other meat");
+    ui.label("Kin");
+    ui.end_row();
+}
+
+fn grid_row(ui: &mut egui::Ui, eater: u32, row: &FoodWebRow, prey: &[u32]) {
+    ui.label(format!("Lineage {eater}"));
+    ui.label(energy_label(*row.plant()));
+    let mut shown = 0;
+    for lineage in prey {
+        let energy = row.meat().get(lineage).copied().unwrap_or_default();
+        shown += energy;
+        let text = egui::RichText::new(energy_label(energy));
+        if *lineage == eater {
+            ui.label(text.color(CANNIBALISM_COLOR));
+        } else {
+            ui.label(text);
+        }
+    }
+    ui.label(energy_label(row.meat_total() - shown));
+    ui.label(energy_label(*row.kin()));
+    ui.end_row();
+}
+
+fn food_web_grid(ui: &mut egui::Ui, food_web: &FoodWeb) {
+    let prey = top_prey(food_web);
+    egui::ScrollArea::both().max_height(300.0).show(ui, |ui| {
+        egui::Grid::new("food_web_grid")
+            .striped(true)
+            .show(ui, |ui| {
+                grid_header(ui, &prey);
+                for (eater, row) in top_eaters(food_web) {
+                    grid_row(ui, *eater, row, &prey);
+                }
+            });
+    });
+}
+
+pub(super) fn food_web_sub_panel(ui: &mut egui::Ui, food_web: &FoodWeb) {
+    ui.label("Energy each lineage has eaten, by what it ate");
+    totals(ui, food_web);
+    food_web_grid(ui, food_web);
+}
@@ -1,15 +1,28 @@
-use bevy::prelude::{Local, Query, Res, ResMut, Resource, With};
+use std::collections::{BTreeMap, HashMap};
+
+use bevy::prelude::{Entity, EventWriter, Local, Query, Res, ResMut, Resource, With};
 use bevy_egui::{egui, EguiContext};
 use bevy_trait_query::ReadTraits;
-use components::{body, eat, grab, grow, lay, see, time, Size};
+use components::{body, eat, grab, grow, lay, mind, see, time, Size};
+use genesis_attributes as attributes;
 use genesis_components as components;
 use genesis_ecosystem as ecosystem;
 use genesis_traits::AttributeDisplay;
 use iyes_loopless::prelude::FixedTimesteps;
 
 use crate::{
+    auditor::{EnergyAudit, EnergyFlow, EnergyLedger},
+    climate::Climate,
+    recorder::ActivationRecorder,
     statistics,
-    ui::{brain_panel, interaction::Selected},
+    ui::{
+        brain_panel,
+        charts::{self, ChartSeries, ChartWindows},
+        energy_flux_panel, food_web_panel,
+        interaction::{ExportActivationsEvent, FollowBugEvent, Selected},
+        lineage_panel::{self, Kin},
+        population_panel::{self, PopulationPanelState},
+    },
 };
 
 #[derive(Debug, PartialEq, Eq, Default)]
@@ -17,23 +30,39 @@ pub enum GlobalPanel {
     #[default]
     Environment,
     Performance,
+    EnergyFlux,
+    FoodWeb,
+    Population,
 }
 
 fn global_panel_buttons(ui: &mut egui::Ui, global_panel_state: &mut GlobalPanel) {
     ui.horizontal(|ui| {
         ui.selectable_value(global_panel_state, GlobalPanel::Environment, "Environment");
         ui.selectable_value(global_panel_state, GlobalPanel::Performance, "Performance");
+        ui.selectable_value(global_panel_state, GlobalPanel::EnergyFlux, "Energy Flux");
+        ui.selectable_value(global_panel_state, GlobalPanel::FoodWeb, "Food Web");
+        ui.selectable_value(global_panel_state, GlobalPanel::Population, "Population");
     });
     ui.end_row();
 }
 
 pub fn global_ui_update_system(
     time: Res<time::SimulationTime>,
+    climate: Res<Climate>,
     count_stats: Res<statistics::CountStats>,
     energy_stats: Res<statistics::EnergyStats>,
+    energy_flux_stats: Res<statistics::EnergyFluxStats>,
     performance_stats: Res<statistics::BugPerformance>,
+    attribute_stats: Res<statistics::AttributeStats>,
+    combat_stats: Res<statistics::CombatStats>,
+    food_web: Res<statistics::FoodWeb>,
+    energy_audit: Res<EnergyAudit>,
+    energy_ledger: Res<EnergyLedger>,
+    attribute_query: Query<(&dyn AttributeDisplay, Option<&components::Egg>)>,
     mut egui_ctx: ResMut<EguiContext>,
     mut panel_state: Local<GlobalPanel>,
+    mut chart_windows: Local<ChartWindows>,
+    mut population_state: Local<PopulationPanelState>,
 ) {
     egui::Window::new("Global Info")
         .anchor(egui::Align2::RIGHT_BOTTOM, [-5.0, -5.0])
@@ -41,16 +70,62 @@ pub fn global_ui_update_system(
             global_panel_buttons(ui, &mut panel_state);
             match *panel_state {
                 GlobalPanel::Environment => {
-                    environment_sub_panel(ui, &time, &energy_stats, &count_stats);
+                    environment_sub_panel(ui, &time, &climate, &energy_stats, &count_stats);
+                    energy_audit_sub_panel(ui, &energy_audit, &energy_ledger);
+                    environment_charts(ui, &mut chart_windows, &count_stats, &energy_stats);
+                }
+                GlobalPanel::Performance => {
+                    population_sub_panel(ui, &performance_stats, &combat_stats);
+                    performance_chart(ui, &mut chart_windows.performance, &performance_stats);
+                }
+                GlobalPanel::EnergyFlux => {
+                    energy_flux_panel::energy_flux_sub_panel(
+                        ui,
+                        &mut chart_windows.energy_flux,
+                        &energy_flux_stats,
+                    );
+                }
+                GlobalPanel::FoodWeb => {
+                    food_web_panel::food_web_sub_panel(ui, &food_web);
+                }
+                GlobalPanel::Population => {
+                    let attributes =
+                        attribute_values(&attribute_query, population_state.include_eggs());
+                    population_panel::population_sub_panel(
+                        ui,
+                        &mut population_state,
+                        &mut chart_windows.attributes,
+                        &attribute_stats,
+                        &attributes,
+                    );
                 }
-                GlobalPanel::Performance => population_sub_panel(ui, &performance_stats),
             };
         });
 }
 
+fn attribute_values(
+    attribute_query: &Query<(&dyn AttributeDisplay, Option<&components::Egg>)>,
+    include_eggs: bool,
+) -> BTreeMap<String, Vec<f32>> {
+    let mut values: BTreeMap<String, Vec<f32>> = BTreeMap::new();
+    for (attrs, egg) in attribute_query.iter() {
+        if egg.is_some() && !include_eggs {
+            continue;
+        }
+        for attr in attrs.iter() {
+            values
+                .entry(attr.name().to_string())
+                .or_default()
+                .push(attr.value());
+        }
+    }
+    values
+}
+
 fn environment_sub_panel(
     ui: &mut egui::Ui,
     time: &Res<time::SimulationTime>,
+    climate: &Res<Climate>,
     energy_stats: &Res<statistics::EnergyStats>,
     count_stats: &Res<statistics::CountStats>,
 ) {
@@ -59,6 +134,7 @@ fn environment_sub_panel(
         energy_stats.current_available_energy()
     ));
     ui.label(format!("Time elapsed: {:.2}", **time));
+    climate_cycle(ui, climate);
     ui.label(format!(
         "Number of adults: {}",
         count_stats.current_adults()
@@ -74,7 +150,44 @@ fn environment_sub_panel(
     ));
 }
 
-fn population_sub_panel(ui: &mut egui::Ui, performance_stats: &statistics::BugPerformance) {
+fn energy_audit_sub_panel(ui: &mut egui::Ui, audit: &EnergyAudit, ledger: &EnergyLedger) {
+    if audit.is_balanced() {
+        ui.label("Energy audit: balanced");
+    } else {
+        ui.label(format!("Energy audit: off by {}", audit.discrepancy()));
+    }
+    ui.collapsing("Energy ledger", |ui| {
+        for flow in EnergyFlow::ALL {
+            ui.label(format!("{}: {}", flow.as_str(), ledger.total(flow)));
+        }
+    });
+}
+
+fn climate_cycle(ui: &mut egui::Ui, climate: &Climate) {
+    ui.horizontal(|ui| {
+        ui.label(format!("Season: {}", climate.season().as_str()));
+        ui.add(
+            egui::ProgressBar::new(climate.season_progress())
+                .desired_width(120.0)
+                .text(format!("warmth {:.2}", climate.warmth())),
+        );
+    });
+    ui.horizontal(|ui| {
+        let time_of_day = if climate.is_day() { "Day" } else { "Night" };
+        ui.label(format!("Time of day: {time_of_day}"));
+        ui.add(
+            egui::ProgressBar::new(climate.day_progress())
+                .desired_width(120.0)
+                .text(format!("light {:.2}", climate.daylight())),
+        );
+    });
+}
+
+fn population_sub_panel(
+    ui: &mut egui::Ui,
+    performance_stats: &statistics::BugPerformance,
+    combat_stats: &statistics::CombatStats,
+) {
     ui.label(format!(
         "Highest energy consumed: {}",
         performance_stats.current_highest_energy_consumed()
@@ -91,6 +204,68 @@ fn population_sub_panel(ui: &mut egui::Ui, performance_stats: &statistics::BugPe
         "Oldest bug age: {:.2}",
         performance_stats.current_oldest_bug()
     ));
+    ui.label(format!(
+        "Kills: {}",
+        combat_stats.kills_by_generation().values().sum::<usize>()
+    ));
+    ui.label(format!(
+        "Predator/prey ratio: {:.2}",
+        combat_stats.current_predator_prey_ratio()
+    ));
+}
+
+fn environment_charts(
+    ui: &mut egui::Ui,
+    chart_windows: &mut ChartWindows,
+    count_stats: &statistics::CountStats,
+    energy_stats: &statistics::EnergyStats,
+) {
+    ui.collapsing("Population chart", |ui| {
+        charts::time_series_chart(
+            ui,
+            "population_chart",
+            &mut chart_windows.population,
+            &[
+                ChartSeries::from_usize("Adults", count_stats.adults()),
+                ChartSeries::from_usize("Juveniles", count_stats.juveniles()),
+                ChartSeries::from_usize("Eggs", count_stats.eggs()),
+            ],
+        );
+    });
+    ui.collapsing("Energy chart", |ui| {
+        charts::time_series_chart(
+            ui,
+            "energy_chart",
+            &mut chart_windows.energy,
+            &[
+                ChartSeries::from_usize("Global energy", energy_stats.available_energy()),
+                ChartSeries::from_usize("Food energy", energy_stats.food_energy()),
+            ],
+        );
+    });
+}
+
+fn performance_chart(
+    ui: &mut egui::Ui,
+    window: &mut charts::TimeWindow,
+    performance_stats: &statistics::BugPerformance,
+) {
+    ui.collapsing("Performance chart", |ui| {
+        charts::time_series_chart(
+            ui,
+            "performance_chart",
+            window,
+            &[
+                ChartSeries::from_usize(
+                    "Highest energy consumed",
+                    performance_stats.highest_energy_consumed(),
+                ),
+                ChartSeries::from_usize("Most eggs laid", performance_stats.most_eggs_laid()),
+                ChartSeries::from_usize("Max generation", performance_stats.max_generation()),
+                ChartSeries::from_f32("Oldest bug age", performance_stats.oldest_bug()),
+            ],
+        );
+    });
 }
 
 #[derive(Debug, Default, Resource)]
@@ -111,6 +286,7 @@ pub enum BugInfoPanel {
     Brain,
     Stats,
     EnergyFlow,
+    Lineage,
 }
 
 fn bug_panel_buttons(ui: &mut egui::Ui, bug_info_panel_state: &mut BugInfoPanel) {
@@ -124,6 +300,7 @@ fn bug_panel_buttons(ui: &mut egui::Ui, bug_info_panel_state: &mut BugInfoPanel)
             BugInfoPanel::EnergyFlow,
             "Energy Flow",
         );
+        ui.selectable_value(bug_info_panel_state, BugInfoPanel::Lineage, "Lineage");
     });
     ui.end_row();
 }
@@ -169,46 +346,58 @@ fn bug_live_sub_panel(ui: &mut egui::Ui, bug_info: &BugLiveInfo) {
 
 pub fn attribute_info_system(
     is_egg_query: Query<&components::Egg, With<Selected>>,
-    attr_query_part: Query<&dyn AttributeDisplay, With<Selected>>,
+    attr_query_part: Query<(&dyn AttributeDisplay, &attributes::TradeOffs), With<Selected>>,
     mut egui_ctx: ResMut<EguiContext>,
     mut panel_state: ResMut<EntityPanelState>,
 ) {
-    let Ok(attr_info_part) = attr_query_part.get_single() else {
+    let Ok((attr_info_part, trade_offs)) = attr_query_part.get_single() else {
         return;
     };
     if is_egg_query.get_single().is_err() {
         if panel_state.bug_info_panel_state == BugInfoPanel::Attributes {
             top_left_info_window("Bug Attribute Info").show(egui_ctx.ctx_mut(), |ui| {
                 bug_panel_buttons(ui, &mut panel_state.bug_info_panel_state);
-                attribute_sub_panel(ui, &attr_info_part);
+                attribute_sub_panel(ui, &attr_info_part, trade_offs);
             });
         }
     } else if panel_state.egg_info_panel_state == EggInfoPanel::Attributes {
         top_left_info_window("Egg Attribute Info").show(egui_ctx.ctx_mut(), |ui| {
             egg_panel_buttons(ui, &mut panel_state.egg_info_panel_state);
-            attribute_sub_panel(ui, &attr_info_part);
+            attribute_sub_panel(ui, &attr_info_part, trade_offs);
         });
     }
 }
 
-fn attribute_sub_panel(ui: &mut egui::Ui, bug_info_part: &ReadTraits<dyn AttributeDisplay>) {
+fn attribute_sub_panel(
+    ui: &mut egui::Ui,
+    bug_info_part: &ReadTraits<dyn AttributeDisplay>,
+    trade_offs: &attributes::TradeOffs,
+) {
     for attr in bug_info_part {
         ui.label(attr.display());
     }
+    for trade_off in trade_offs.iter() {
+        ui.label(format!("{}: {:.3}", trade_off.name(), trade_off.value()));
+    }
 }
 
 pub fn bug_brain_info_system(
-    brain_query: Query<brain_panel::BugBrainInfo, With<Selected>>,
+    brain_query: Query<(Entity, brain_panel::BugBrainInfo), With<Selected>>,
+    recorder: Res<ActivationRecorder>,
+    mut ev_export: EventWriter<ExportActivationsEvent>,
     mut egui_ctx: ResMut<EguiContext>,
     mut panel_state: ResMut<EntityPanelState>,
 ) {
-    let Ok(bug_info) = brain_query.get_single() else {
+    let Ok((entity, bug_info)) = brain_query.get_single() else {
         return;
     };
     if panel_state.bug_info_panel_state == BugInfoPanel::Brain {
         top_left_info_window("Bug Brain Info").show(egui_ctx.ctx_mut(), |ui| {
             bug_panel_buttons(ui, &mut panel_state.bug_info_panel_state);
             brain_panel::bug_brain_sub_panel(ui, &bug_info);
+            if recorder.entity() == Some(entity) && brain_panel::activation_traces(ui, &recorder) {
+                ev_export.send(ExportActivationsEvent);
+            }
         });
     }
 }
@@ -236,6 +425,75 @@ fn bug_stat_sub_panel(ui: &mut egui::Ui, bug_stats: &BugStatsInfo) {
     ui.label(format!("Eggs laid: {}", **bug_stats.1));
 }
 
+type KinInfo<'a> = (
+    Entity,
+    &'a components::Relations,
+    &'a dyn AttributeDisplay,
+    &'a mind::Mind,
+    Option<&'a components::Egg>,
+);
+
+fn living_kin(kin_query: &Query<KinInfo>) -> HashMap<u32, Kin> {
+    kin_query
+        .iter()
+        .map(|(entity, relations, attrs, mind, egg)| {
+            let attributes = attrs
+                .iter()
+                .map(|attr| (attr.name().to_string(), attr.value()))
+                .collect();
+            let kin = Kin::new(
+                Some(entity),
+                relations,
+                attributes,
+                Some(mind.clone()),
+                egg.is_some(),
+            );
+            (relations.entity().0, kin)
+        })
+        .collect()
+}
+
+pub fn bug_lineage_info_system(
+    selected_query: Query<&components::Relations, (With<Selected>, With<time::Age>)>,
+    kin_query: Query<KinInfo>,
+    family_tree: Res<statistics::FamilyTree>,
+    mut ev_follow_bug: EventWriter<FollowBugEvent>,
+    mut egui_ctx: ResMut<EguiContext>,
+    mut panel_state: ResMut<EntityPanelState>,
+    mut depth: Local<Option<usize>>,
+) {
+    let Ok(selected_relations) = selected_query.get_single() else {
+        return;
+    };
+    if panel_state.bug_info_panel_state != BugInfoPanel::Lineage {
+        return;
+    }
+    let living = living_kin(&kin_query);
+    let Some(selected) = living.get(&selected_relations.entity().0) else {
+        return;
+    };
+    let find = |id: u32| {
+        living.get(&id).cloned().or_else(|| {
+            family_tree
+                .dead_relative(id)
+                .map(|(relations, attributes)| {
+                    let attributes = attributes
+                        .iter()
+                        .map(|(name, value)| (name.clone(), *value))
+                        .collect();
+                    Kin::new(None, relations, attributes, None, false)
+                })
+        })
+    };
+    let depth = depth.get_or_insert(5);
+    top_left_info_window("Bug Lineage").show(egui_ctx.ctx_mut(), |ui| {
+        bug_panel_buttons(ui, &mut panel_state.bug_info_panel_state);
+        if let Some(entity) = lineage_panel::bug_lineage_sub_panel(ui, depth, selected, &find) {
+            ev_follow_bug.send(FollowBugEvent(entity));
+        }
+    });
+}
+
 type EnergyFlowInfo<'a> = (
     &'a eat::EatingSum,
     &'a grab::GrabbingSum,
@@ -362,16 +620,19 @@ fn egg_live_sub_panel(ui: &mut egui::Ui, egg_info: &EggLiveInfo) {
     ui.label(format!("Generation: {}", &egg_info.1 .0));
 }
 
-type FoodInfo<'a> = &'a ecosystem::Food;
+type FoodInfo<'a> = (&'a ecosystem::Food, Option<&'a components::PlantType>);
 
 pub fn food_info_panel_system(
     food_query: Query<FoodInfo, With<Selected>>,
     mut egui_ctx: ResMut<EguiContext>,
 ) {
-    let Ok(food_info) = food_query.get_single() else {
+    let Ok((food_info, plant_type)) = food_query.get_single() else {
         return;
     };
     top_left_info_window("Food Info").show(egui_ctx.ctx_mut(), |ui| {
         ui.label(format!("Energy: {}", &food_info.energy()));
+        if let Some(plant_type) = plant_type {
+            ui.label(format!("Plant type: {}", **plant_type));
+        }
     });
 }
@@ -5,7 +5,7 @@ use bevy::{
     prelude::{
         info, AssetServer, Camera, Color, Commands, Component, Entity, EventReader, EventWriter,
         GlobalTransform, Input, KeyCode, MouseButton, OrthographicProjection, Query,
-        ReflectComponent, Res, ResMut, Transform, Vec2, Vec3, With,
+        ReflectComponent, Res, ResMut, Resource, Transform, Vec2, Vec3, With, Without,
     },
     reflect::Reflect,
     render::camera::RenderTarget,
@@ -22,15 +22,42 @@ use genesis_config as config;
 use genesis_ecosystem::Ecosystem;
 use iyes_loopless::prelude::*;
 
-use crate::{genesis_serde, simulation::SimulationSpeed, spawning, statistics};
+use crate::{
+    auditor::{EnergyFlow, EnergyLedger},
+    genesis_serde,
+    simulation::SimulationSpeed,
+    spawning, statistics,
+};
+
+/// Whether the camera is panned by hand or locked onto the selected bug.
+#[derive(Resource, Debug, Default, PartialEq, Eq, Clone, Copy)]
+pub enum CameraMode {
+    #[default]
+    Free,
+    Follow,
+}
 
 pub fn move_camera_system(
     kb_input: Res<Input<KeyCode>>,
     time: Res<Time>,
+    mut camera_mode: ResMut<CameraMode>,
     mut camera_query: Query<(&mut Transform, &mut OrthographicProjection), With<Camera>>,
+    selected_query: Query<&Transform, (With<Selected>, Without<Camera>)>,
 ) {
     let time_delta = time.delta().as_secs_f32();
     let (mut transform, _) = camera_query.single_mut();
+    // Panning by hand breaks the lock on the followed bug.
+    if kb_input.any_pressed([KeyCode::A, KeyCode::D, KeyCode::S, KeyCode::W]) {
+        *camera_mode = CameraMode::Free;
+    }
+    if *camera_mode == CameraMode::Follow {
+        if let Ok(target) = selected_query.get_single() {
+            transform.translation.x = target.translation.x;
+            transform.translation.y = target.translation.y;
+            return;
+        }
+        *camera_mode = CameraMode::Free;
+    }
     // Panning.
     let mut x_direction = 0.0;
     let mut y_direction = 0.0;
@@ -156,9 +183,35 @@ pub fn select_sprite_system(
     });
 }
 
+/// Selects a bug and locks the camera onto it.
+#[derive(Debug)]
+pub struct FollowBugEvent(pub Entity);
+
+pub fn follow_bug_system(
+    mut commands: Commands,
+    mut ev_follow_bug: EventReader<FollowBugEvent>,
+    mut camera_mode: ResMut<CameraMode>,
+    mut sprite_query: Query<(Entity, &mut Sprite, &body::OriginalColor)>,
+) {
+    let Some(FollowBugEvent(target)) = ev_follow_bug.iter().last() else {
+        return;
+    };
+    for (entity, mut sprite, original_color) in sprite_query.iter_mut() {
+        if entity == *target {
+            commands.entity(entity).insert(Selected);
+            sprite.color = Color::RED;
+        } else {
+            commands.entity(entity).remove::<Selected>();
+            sprite.color = original_color.0;
+        }
+    }
+    *camera_mode = CameraMode::Follow;
+}
+
 pub fn kill_selected_system(
     kb_input: Res<Input<KeyCode>>,
     mut ecosystem: ResMut<Ecosystem>,
+    mut ledger: ResMut<EnergyLedger>,
     mut bug_query: Query<&mut body::Vitality, With<Selected>>,
 ) {
     if !kb_input.pressed(KeyCode::Delete) {
@@ -168,6 +221,11 @@ pub fn kill_selected_system(
         return;
     };
     let energy_extract = vitality.health_mut().take_all_energy();
+    ledger.record(
+        EnergyFlow::Killed,
+        "kill_selected_system",
+        energy_extract.amount(),
+    );
     ecosystem.return_energy(energy_extract);
 }
 
@@ -230,10 +288,23 @@ pub struct LoadBugEvent;
 #[derive(Debug)]
 pub struct SaveBugEvent;
 
+#[derive(Debug)]
+pub struct ExportActivationsEvent;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BrainExportFormat {
+    Dot,
+    Json,
+}
+
+#[derive(Debug)]
+pub struct ExportBrainEvent(pub BrainExportFormat);
+
 pub fn bug_serde_widget(
     mut ev_save_sim: EventWriter<SaveSimulationEvent>,
     mut ev_load_bug: EventWriter<LoadBugEvent>,
     mut ev_save_bug: EventWriter<SaveBugEvent>,
+    mut ev_export_brain: EventWriter<ExportBrainEvent>,
     mut egui_ctx: ResMut<EguiContext>,
     bug_query: Query<Entity, (With<time::Age>, With<Selected>)>,
 ) {
@@ -247,9 +318,18 @@ pub fn bug_serde_widget(
                 if ui.button("Load bug").clicked() {
                     ev_load_bug.send(LoadBugEvent);
                 };
-                if bug_query.get_single().is_ok() && ui.button("Save bug").clicked() {
+                if bug_query.get_single().is_err() {
+                    return;
+                }
+                if ui.button("Save bug").clicked() {
                     ev_save_bug.send(SaveBugEvent);
                 }
+                if ui.button("Export brain (DOT)").clicked() {
+                    ev_export_brain.send(ExportBrainEvent(BrainExportFormat::Dot));
+                }
+                if ui.button("Export brain (JSON)").clicked() {
+                    ev_export_brain.send(ExportBrainEvent(BrainExportFormat::Json));
+                }
             })
         });
 }
@@ -277,6 +357,7 @@ pub fn spawn_at_mouse(
     asset_server: Res<AssetServer>,
     genome: Res<attributes::Genome>,
     mut ecosystem: ResMut<Ecosystem>,
+    mut ledger: ResMut<EnergyLedger>,
     loaded_blueprint: ResMut<genesis_serde::LoadedBlueprint>,
     wnds: Res<Windows>,
     mouse_button: Res<Input<MouseButton>>,
@@ -294,6 +375,7 @@ pub fn spawn_at_mouse(
     let Some(energy) = ecosystem.request_energy(WorldConfig::global().start_energy) else {
         return;
     };
+    ledger.record(EnergyFlow::Spawned, "spawn_at_mouse", energy.amount());
 
     spawning::spawn_egg(
         &mut commands,
@@ -0,0 +1,230 @@
+use std::collections::{BTreeMap, VecDeque};
+
+use bevy::prelude::Entity;
+use bevy_egui::egui;
+use genesis_components::{mind::Mind, Relations};
+
+/// A relative of the selected bug, either still in the simulation or from the family tree.
+#[derive(Debug, Clone)]
+pub(super) struct Kin {
+    entity: Option<Entity>,
+    color: String,
+    parent: Option<u32>,
+    children: Vec<u32>,
+    attributes: BTreeMap<String, f32>,
+    mind: Option<Mind>,
+    is_egg: bool,
+}
+
+impl Kin {
+    pub fn new(
+        entity: Option<Entity>,
+        relations: &Relations,
+        attributes: BTreeMap<String, f32>,
+        mind: Option<Mind>,
+        is_egg: bool,
+    ) -> Self {
+        Self {
+            entity,
+            color: relations.entity().1.clone(),
+            parent: *relations.parent(),
+            children: relations.children().clone(),
+            attributes,
+            mind,
+            is_egg,
+        }
+    }
+
+    const fn status(&self) -> &'static str {
+        match (self.entity, self.is_egg) {
+            (None, _) => "dead",
+            (Some(_), true) => "egg",
+            (Some(_), false) => "alive",
+        }
+    }
+}
+
+/// Walks up the parent chain, closest ancestor first.
+fn ancestors(selected: &Kin, find: &dyn Fn(u32) -> Option<Kin>, depth: usize) -> Vec<Kin> {
+    let mut ancestors = Vec::new();
+    let mut parent = selected.parent;
+    while let Some(id) = parent {
+        if ancestors.len() == depth {
+            break;
+        }
+        let Some(ancestor) = find(id) else {
+            break;
+        };
+        parent = ancestor.parent;
+        ancestors.push(ancestor);
+    }
+    ancestors
+}
+
+/// Finds descendants still in the simulation, following the family tree through dead ones.
+fn living_descendants(
+    selected: &Kin,
+    find: &dyn Fn(u32) -> Option<Kin>,
+    depth: usize,
+) -> Vec<(usize, Kin)> {
+    let mut descendants = Vec::new();
+    let mut queue: VecDeque<(usize, u32)> = selected.children.iter().map(|id| (1, *id)).collect();
+    while let Some((generation, id)) = queue.pop_front() {
+        if generation > depth {
+            continue;
+        }
+        let Some(kin) = find(id) else {
+            continue;
+        };
+        queue.extend(kin.children.iter().map(|child| (generation + 1, *child)));
+        if kin.entity.is_some() {
+            descendants.push((generation, kin));
+        }
+    }
+    descendants
+}
+
+fn attribute_diffs(ui: &mut egui::Ui, selected: &Kin, kin: &Kin) {
+    for (name, value) in &kin.attributes {
+        let Some(selected_value) = selected.attributes.get(name) else {
+            continue;
+        };
+        let difference = value - selected_value;
+        if difference.abs() > f32::EPSILON {
+            ui.label(format!("{name}: {value:.2} ({difference:+.2})"));
+        }
+    }
+}
+
+fn brain_diff(ui: &mut egui::Ui, selected: &Kin, kin: &Kin) {
+    let (Some(theirs), Some(mine)) = (&kin.mind, &selected.mind) else {
+        ui.label("Brain: unknown");
+        return;
+    };
+    let diff = mine.diff(theirs);
+    ui.label(format!("Brain: {} changes", diff.changes().len()));
+    for change in diff.changes() {
+        ui.label(change.to_string());
+    }
+}
+
+fn relative_row(
+    ui: &mut egui::Ui,
+    selected: &Kin,
+    kin: &Kin,
+    label: &str,
+    clicked: &mut Option<Entity>,
+) {
+    ui.horizontal(|ui| {
+        egui::CollapsingHeader::new(format!("{label} {} ({})", kin.color, kin.status()))
+            .id_source((label, &kin.color))
+            .show(ui, |ui| {
+                attribute_diffs(ui, selected, kin);
+                brain_diff(ui, selected, kin);
+            });
+        if let Some(entity) = kin.entity {
+            if ui.button("Select").clicked() {
+                *clicked = Some(entity);
+            }
+        }
+    });
+}
+
+/// Ancestors and living descendants of the selected bug, returning a relative to select.
+pub(super) fn bug_lineage_sub_panel(
+    ui: &mut egui::Ui,
+    depth: &mut usize,
+    selected: &Kin,
+    find: &dyn Fn(u32) -> Option<Kin>,
+) -> Option<Entity> {
+    let mut clicked = None;
+    ui.add(egui::Slider::new(depth, 1..=20).text("Generations"));
+    ui.label("Differences are shown against the selected bug.");
+
+    let ancestors = ancestors(selected, find, *depth);
+    ui.collapsing(format!("Ancestors ({})", ancestors.len()), |ui| {
+        for (i, ancestor) in ancestors.iter().enumerate() {
+            relative_row(ui, selected, ancestor, &format!("-{}", i + 1), &mut clicked);
+        }
+    });
+
+    let descendants = living_descendants(selected, find, *depth);
+    ui.collapsing(
+        format!("Living descendants ({})", descendants.len()),
+        |ui| {
+            for (generation, descendant) in &descendants {
+                relative_row(
+                    ui,
+                    selected,
+                    descendant,
+                    &format!("+{generation}"),
+                    &mut clicked,
+                );
+            }
+        },
+    );
+    clicked
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use bevy::prelude::Color;
+
+    use super::*;
+
+    fn relations(index: u32, parent: Option<u32>, children: &[u32]) -> Relations {
+        let mut relations = Relations::new(
+            (Entity::from_raw(index), Color::WHITE),
+            parent.map(Entity::from_raw),
+        );
+        for child in children {
+            relations.add_child(Entity::from_raw(*child));
+        }
+        relations
+    }
+
+    #[test]
+    fn lineage_follows_the_family_tree() {
+        // 0 -> 1 -> 2 -> {3, 4}, where 1 and 3 have died.
+        let family = [
+            (0, None, vec![1], true),
+            (1, Some(0), vec![2], false),
+            (2, Some(1), vec![3, 4], true),
+            (3, Some(2), vec![], false),
+            (4, Some(2), vec![], true),
+        ];
+        let mut kin = HashMap::new();
+        for (index, parent, children, alive) in family {
+            let relations = relations(index, parent, &children);
+            let entity = alive.then(|| Entity::from_raw(index));
+            kin.insert(
+                relations.entity().0,
+                Kin::new(entity, &relations, BTreeMap::new(), None, false),
+            );
+        }
+        let find = |id: u32| kin.get(&id).cloned();
+        let id = |index: u32| relations(index, None, &[]).entity().0;
+
+        let selected = &kin[&id(2)];
+        let ancestors = ancestors(selected, &find, 5);
+        let ancestor_entities: Vec<_> = ancestors.iter().map(|kin| kin.entity).collect();
+        assert_eq!(ancestor_entities, vec![None, Some(Entity::from_raw(0))]);
+        assert_eq!(super::ancestors(selected, &find, 1).len(), 1);
+
+        let oldest = &kin[&id(0)];
+        let descendants: Vec<_> = living_descendants(oldest, &find, 5)
+            .into_iter()
+            .map(|(generation, kin)| (generation, kin.entity))
+            .collect();
+        assert_eq!(
+            descendants,
+            vec![
+                (2, Some(Entity::from_raw(2))),
+                (3, Some(Entity::from_raw(4)))
+            ]
+        );
+        assert!(living_descendants(oldest, &find, 1).is_empty());
+    }
+}
@@ -1,10 +1,20 @@
 mod brain_panel;
+mod charts;
+mod energy_flux_panel;
+mod food_web_panel;
 mod info_panels;
 mod interaction;
+mod lineage_panel;
 pub mod menus;
+mod population_panel;
+mod roster_panel;
 
 use bevy::prelude::{App, Plugin, SystemSet};
-pub use interaction::{LoadBugEvent, SaveBugEvent, SaveSimulationEvent, Selected};
+pub use brain_panel::neuron_name;
+pub use interaction::{
+    BrainExportFormat, ExportActivationsEvent, ExportBrainEvent, LoadBugEvent, SaveBugEvent,
+    SaveSimulationEvent, Selected,
+};
 use iyes_loopless::prelude::*;
 
 use crate::{conditions, SimState};
@@ -20,6 +30,7 @@ pub fn interaction_system_set() -> SystemSet {
         .with_system(interaction::bug_serde_widget)
         .with_system(interaction::bug_spawner_widget)
         .with_system(interaction::kill_selected_system)
+        .with_system(interaction::follow_bug_system)
         .into()
 }
 
@@ -34,6 +45,8 @@ pub fn info_panels_system_set() -> SystemSet {
         .with_system(info_panels::bug_brain_info_system)
         .with_system(info_panels::bug_stats_info_system)
         .with_system(info_panels::energy_flow_info_system)
+        .with_system(info_panels::bug_lineage_info_system)
+        .with_system(roster_panel::bug_roster_system)
         .into()
 }
 
@@ -71,8 +84,12 @@ impl Plugin for GenesisUiPlugin {
             .add_system_set(game_time_system_set())
             .add_system_set(info_panels_system_set())
             .insert_resource(info_panels::EntityPanelState::default())
+            .init_resource::<interaction::CameraMode>()
+            .add_event::<interaction::FollowBugEvent>()
             .add_event::<interaction::SaveSimulationEvent>()
             .add_event::<interaction::LoadBugEvent>()
-            .add_event::<interaction::SaveBugEvent>();
+            .add_event::<interaction::SaveBugEvent>()
+            .add_event::<interaction::ExportActivationsEvent>()
+            .add_event::<interaction::ExportBrainEvent>();
     }
 }
@@ -0,0 +1,131 @@
+use std::collections::BTreeMap;
+
+use bevy_egui::egui::{
+    self,
+    plot::{Bar, BarChart, Plot},
+};
+
+use crate::{
+    statistics::{AttributeStats, Quantiles},
+    ui::charts::{self, ChartSeries, TimeWindow},
+};
+
+const HISTOGRAM_BINS: usize = 20;
+
+/// Which attribute the population panel shows and whether eggs are counted.
+#[derive(Debug, Default)]
+pub struct PopulationPanelState {
+    attribute: Option<String>,
+    include_eggs: bool,
+}
+
+impl PopulationPanelState {
+    pub const fn include_eggs(&self) -> bool {
+        self.include_eggs
+    }
+}
+
+/// Splits the values into equal width bins, returning each bin's centre, width and count.
+fn histogram(values: &[f32], bins: usize) -> Vec<(f64, f64, usize)> {
+    let Some(min) = values.iter().copied().reduce(f32::min) else {
+        return Vec::new();
+    };
+    let max = values.iter().copied().reduce(f32::max).unwrap_or(min);
+    let min = f64::from(min);
+    let range = f64::from(max) - min;
+    if range <= f64::EPSILON {
+        return vec![(min, 1.0, values.len())];
+    }
+
+    let width = range / bins as f64;
+    let mut counts = vec![0; bins];
+    for value in values {
+        let bin = ((f64::from(*value) - min) / width) as usize;
+        counts[bin.min(bins - 1)] += 1;
+    }
+    counts
+        .into_iter()
+        .enumerate()
+        .map(|(i, count)| (width.mul_add(i as f64 + 0.5, min), width, count))
+        .collect()
+}
+
+fn attribute_selector(ui: &mut egui::Ui, state: &mut PopulationPanelState, names: &[&str]) {
+    ui.horizontal(|ui| {
+        egui::ComboBox::from_id_source("population_attribute")
+            .selected_text(state.attribute.as_deref().unwrap_or("Select attribute"))
+            .show_ui(ui, |ui| {
+                for name in names {
+                    ui.selectable_value(&mut state.attribute, Some((*name).to_string()), *name);
+                }
+            });
+        ui.checkbox(&mut state.include_eggs, "Include eggs");
+    });
+}
+
+fn attribute_histogram(ui: &mut egui::Ui, name: &str, values: &[f32]) {
+    ui.label(format!("{} individuals", values.len()));
+    let bars = histogram(values, HISTOGRAM_BINS)
+        .into_iter()
+        .map(|(centre, width, count)| Bar::new(centre, count as f64).width(width))
+        .collect();
+    Plot::new("population_histogram")
+        .height(180.0)
+        .show(ui, |plot_ui| {
+            plot_ui.bar_chart(BarChart::new(bars).name(name));
+        });
+}
+
+fn attribute_history(
+    ui: &mut egui::Ui,
+    window: &mut TimeWindow,
+    attribute_stats: &AttributeStats,
+    name: &str,
+) {
+    let history = attribute_stats.history(name);
+    let quantile = |f: fn(&Quantiles) -> f32| history.iter().map(f).collect::<Vec<_>>();
+    let series = [
+        ChartSeries::from_f32("min", &quantile(|q| q.min)),
+        ChartSeries::from_f32("lower quartile", &quantile(|q| q.lower_quartile)),
+        ChartSeries::from_f32("median", &quantile(|q| q.median)),
+        ChartSeries::from_f32("upper quartile", &quantile(|q| q.upper_quartile)),
+        ChartSeries::from_f32("max", &quantile(|q| q.max)),
+    ];
+    ui.label("History across living bugs");
+    charts::time_series_chart(ui, "population_history", window, &series);
+}
+
+/// Histogram of one attribute across the current population and how it has drifted over time.
+pub(super) fn population_sub_panel(
+    ui: &mut egui::Ui,
+    state: &mut PopulationPanelState,
+    window: &mut TimeWindow,
+    attribute_stats: &AttributeStats,
+    attributes: &BTreeMap<String, Vec<f32>>,
+) {
+    let names: Vec<&str> = attributes.keys().map(String::as_str).collect();
+    attribute_selector(ui, state, &names);
+    let Some(name) = state.attribute.as_deref() else {
+        return;
+    };
+    let values = attributes.get(name).map_or(&[][..], Vec::as_slice);
+    attribute_histogram(ui, name, values);
+    attribute_history(ui, window, attribute_stats, name);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn histogram_bins_cover_the_range() {
+        assert!(histogram(&[], 4).is_empty());
+        assert_eq!(histogram(&[2.0, 2.0], 4), vec![(2.0, 1.0, 2)]);
+
+        let bins = histogram(&[0.0, 1.0, 1.5, 4.0], 4);
+        let counts: Vec<usize> = bins.iter().map(|(_, _, count)| *count).collect();
+        assert_eq!(counts, vec![1, 2, 0, 1]);
+        assert_eq!(bins[0].0, 0.5);
+        assert_eq!(bins[0].1, 1.0);
+    }
+}
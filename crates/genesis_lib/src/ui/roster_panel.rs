@@ -0,0 +1,359 @@
+use std::cmp::Ordering;
+
+use bevy::prelude::{Entity, EventWriter, Local, Query, Res, ResMut, With, Without};
+use bevy_egui::{egui, EguiContext};
+use components::{body, eat, lay, time, Egg, Generation};
+use genesis_color::rgb_to_hex;
+use genesis_components as components;
+use genesis_traits::AttributeDisplay;
+
+use crate::ui::interaction::{CameraMode, FollowBugEvent, Selected};
+
+const MAX_ROWS: usize = 200;
+
+/// One row in the roster of living bugs.
+#[derive(Debug, Clone, PartialEq)]
+struct RosterEntry {
+    entity: Entity,
+    generation: usize,
+    age: f32,
+    energy_consumed: usize,
+    eggs_laid: usize,
+    species: String,
+    color: egui::Color32,
+    attributes: Vec<(String, f32)>,
+}
+
+impl RosterEntry {
+    fn attribute(&self, name: &str) -> Option<f32> {
+        self.attributes
+            .iter()
+            .find(|(attribute, _)| attribute == name)
+            .map(|(_, value)| *value)
+    }
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+enum RosterSort {
+    #[default]
+    Generation,
+    Age,
+    EnergyConsumed,
+    EggsLaid,
+    Attribute,
+}
+
+impl RosterSort {
+    const fn as_str(self) -> &'static str {
+        match self {
+            Self::Generation => "Generation",
+            Self::Age => "Age",
+            Self::EnergyConsumed => "Energy consumed",
+            Self::EggsLaid => "Eggs laid",
+            Self::Attribute => "Attribute",
+        }
+    }
+
+    fn compare(self, a: &RosterEntry, b: &RosterEntry, attribute: Option<&str>) -> Ordering {
+        match self {
+            Self::Generation => a.generation.cmp(&b.generation),
+            Self::Age => a.age.total_cmp(&b.age),
+            Self::EnergyConsumed => a.energy_consumed.cmp(&b.energy_consumed),
+            Self::EggsLaid => a.eggs_laid.cmp(&b.eggs_laid),
+            Self::Attribute => {
+                let value = |entry: &RosterEntry| {
+                    attribute
+                        .and_then(|name| entry.attribute(name))
+                        .unwrap_or(f32::NEG_INFINITY)
+                };
+                value(a).total_cmp(&value(b))
+            }
+        }
+    }
+}
+
+/// Shortcuts that jump straight to a notable bug.
+#[derive(Debug, Clone, Copy)]
+enum Shortcut {
+    Oldest,
+    MostProlific,
+    HighestGeneration,
+}
+
+impl Shortcut {
+    const ALL: [Self; 3] = [Self::Oldest, Self::MostProlific, Self::HighestGeneration];
+
+    const fn as_str(self) -> &'static str {
+        match self {
+            Self::Oldest => "Oldest",
+            Self::MostProlific => "Most prolific",
+            Self::HighestGeneration => "Highest generation",
+        }
+    }
+
+    const fn sort(self) -> RosterSort {
+        match self {
+            Self::Oldest => RosterSort::Age,
+            Self::MostProlific => RosterSort::EggsLaid,
+            Self::HighestGeneration => RosterSort::Generation,
+        }
+    }
+
+    fn pick(self, entries: &[RosterEntry]) -> Option<Entity> {
+        entries
+            .iter()
+            .max_by(|a, b| self.sort().compare(a, b, None))
+            .map(|entry| entry.entity)
+    }
+}
+
+#[derive(Debug)]
+pub struct RosterState {
+    search: String,
+    sort: RosterSort,
+    descending: bool,
+    attribute: Option<String>,
+}
+
+impl Default for RosterState {
+    fn default() -> Self {
+        Self {
+            search: String::new(),
+            sort: RosterSort::default(),
+            descending: true,
+            attribute: None,
+        }
+    }
+}
+
+impl RosterState {
+    /// Matches the search text against the species colour, case insensitively.
+    fn matches(&self, entry: &RosterEntry) -> bool {
+        let search = self.search.trim().to_lowercase();
+        search.is_empty() || entry.species.to_lowercase().contains(&search)
+    }
+
+    fn visible<'a>(&self, entries: &'a [RosterEntry]) -> Vec<&'a RosterEntry> {
+        let mut visible: Vec<&RosterEntry> =
+            entries.iter().filter(|entry| self.matches(entry)).collect();
+        visible.sort_by(|a, b| {
+            let ordering = self.sort.compare(a, b, self.attribute.as_deref());
+            if self.descending {
+                ordering.reverse()
+            } else {
+                ordering
+            }
+        });
+        visible
+    }
+}
+
+type RosterQuery<'a> = (
+    Entity,
+    &'a Generation,
+    &'a time::Age,
+    &'a eat::EnergyConsumed,
+    &'a lay::EggsLaid,
+    &'a body::OriginalColor,
+    &'a dyn AttributeDisplay,
+);
+
+fn roster_entries(bug_query: &Query<RosterQuery, Without<Egg>>) -> Vec<RosterEntry> {
+    bug_query
+        .iter()
+        .map(
+            |(entity, generation, age, energy_consumed, eggs_laid, color, attrs)| {
+                let color = color.0;
+                RosterEntry {
+                    entity,
+                    generation: generation.0,
+                    age: age.elapsed_secs(),
+                    energy_consumed: energy_consumed.0,
+                    eggs_laid: eggs_laid.0,
+                    species: rgb_to_hex(color.r(), color.g(), color.b()),
+                    color: egui::Color32::from_rgb(
+                        (color.r() * 255.0) as u8,
+                        (color.g() * 255.0) as u8,
+                        (color.b() * 255.0) as u8,
+                    ),
+                    attributes: attrs
+                        .iter()
+                        .map(|attr| (attr.name().to_string(), attr.value()))
+                        .collect(),
+                }
+            },
+        )
+        .collect()
+}
+
+fn roster_controls(ui: &mut egui::Ui, state: &mut RosterState, attribute_names: &[String]) {
+    ui.horizontal(|ui| {
+        ui.label("Species");
+        ui.text_edit_singleline(&mut state.search);
+    });
+    ui.horizontal(|ui| {
+        egui::ComboBox::from_id_source("roster_attribute")
+            .selected_text(state.attribute.as_deref().unwrap_or("Attribute"))
+            .show_ui(ui, |ui| {
+                for name in attribute_names {
+                    ui.selectable_value(&mut state.attribute, Some(name.clone()), name);
+                }
+            });
+        egui::ComboBox::from_id_source("roster_sort")
+            .selected_text(format!("Sort by {}", state.sort.as_str()))
+            .show_ui(ui, |ui| {
+                for sort in [
+                    RosterSort::Generation,
+                    RosterSort::Age,
+                    RosterSort::EnergyConsumed,
+                    RosterSort::EggsLaid,
+                    RosterSort::Attribute,
+                ] {
+                    ui.selectable_value(&mut state.sort, sort, sort.as_str());
+                }
+            });
+        let direction = if state.descending { "⏷" } else { "⏶" };
+        if ui.button(direction).clicked() {
+            state.descending = !state.descending;
+        }
+    });
+}
+
+fn roster_grid(
+    ui: &mut egui::Ui,
+    state: &RosterState,
+    entries: &[&RosterEntry],
+    selected: Option<Entity>,
+) -> Option<Entity> {
+    let mut clicked = None;
+    egui::ScrollArea::vertical()
+        .max_height(300.0)
+        .show(ui, |ui| {
+            egui::Grid::new("roster_grid").striped(true).show(ui, |ui| {
+                ui.label("Species");
+                ui.label("Generation");
+                ui.label("Age");
+                ui.label("Energy");
+                ui.label("Eggs");
+                ui.label(state.attribute.as_deref().unwrap_or(""));
+                ui.end_row();
+                for entry in entries.iter().take(MAX_ROWS) {
+                    let is_selected = selected == Some(entry.entity);
+                    let species =
+                        egui::RichText::new(format!("⏺ {}", entry.species)).color(entry.color);
+                    if ui.selectable_label(is_selected, species).clicked() {
+                        clicked = Some(entry.entity);
+                    }
+                    ui.label(entry.generation.to_string());
+                    ui.label(format!("{:.0}", entry.age));
+                    ui.label(entry.energy_consumed.to_string());
+                    ui.label(entry.eggs_laid.to_string());
+                    let attribute = state
+                        .attribute
+                        .as_deref()
+                        .and_then(|name| entry.attribute(name));
+                    ui.label(attribute.map_or_else(String::new, |value| format!("{value:.2}")));
+                    ui.end_row();
+                }
+            });
+        });
+    clicked
+}
+
+pub fn bug_roster_system(
+    bug_query: Query<RosterQuery, Without<Egg>>,
+    selected_query: Query<Entity, With<Selected>>,
+    camera_mode: Res<CameraMode>,
+    mut ev_follow_bug: EventWriter<FollowBugEvent>,
+    mut egui_ctx: ResMut<EguiContext>,
+    mut state: Local<RosterState>,
+) {
+    let entries = roster_entries(&bug_query);
+    let mut attribute_names: Vec<String> = entries
+        .iter()
+        .flat_map(|entry| entry.attributes.iter().map(|(name, _)| name.clone()))
+        .collect();
+    attribute_names.sort();
+    attribute_names.dedup();
+    let selected = selected_query.get_single().ok();
+
+    egui::Window::new("Bugs")
+        .default_pos([5.0, 300.0])
+        .show(egui_ctx.ctx_mut(), |ui| {
+            ui.horizontal(|ui| {
+                ui.label("Jump to");
+                for shortcut in Shortcut::ALL {
+                    if ui.button(shortcut.as_str()).clicked() {
+                        if let Some(entity) = shortcut.pick(&entries) {
+                            ev_follow_bug.send(FollowBugEvent(entity));
+                        }
+                    }
+                }
+            });
+            if *camera_mode == CameraMode::Follow {
+                ui.label("Following the selected bug. Pan to stop.");
+            }
+            roster_controls(ui, &mut state, &attribute_names);
+            let visible = state.visible(&entries);
+            ui.label(format!("{} of {} bugs", visible.len(), entries.len()));
+            if let Some(entity) = roster_grid(ui, &state, &visible, selected) {
+                ev_follow_bug.send(FollowBugEvent(entity));
+            }
+        });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(index: u32, generation: usize, age: f32, eggs_laid: usize) -> RosterEntry {
+        RosterEntry {
+            entity: Entity::from_raw(index),
+            generation,
+            age,
+            energy_consumed: 0,
+            eggs_laid,
+            species: format!("#00000{index}"),
+            color: egui::Color32::BLACK,
+            attributes: vec![("EyeRange".to_string(), index as f32)],
+        }
+    }
+
+    #[test]
+    fn roster_sorts_filters_and_jumps() {
+        let entries = [
+            entry(1, 3, 10.0, 0),
+            entry(2, 1, 50.0, 4),
+            entry(3, 2, 5.0, 2),
+        ];
+
+        let mut state = RosterState::default();
+        let order = |state: &RosterState| -> Vec<u32> {
+            state
+                .visible(&entries)
+                .iter()
+                .map(|entry| entry.entity.index())
+                .collect()
+        };
+        assert_eq!(order(&state), vec![1, 3, 2]);
+
+        state.sort = RosterSort::Attribute;
+        state.attribute = Some("EyeRange".to_string());
+        state.descending = false;
+        assert_eq!(order(&state), vec![1, 2, 3]);
+
+        state.search = "0002".to_string();
+        assert_eq!(order(&state), vec![2]);
+
+        assert_eq!(Shortcut::Oldest.pick(&entries), Some(Entity::from_raw(2)));
+        assert_eq!(
+            Shortcut::MostProlific.pick(&entries),
+            Some(Entity::from_raw(2))
+        );
+        assert_eq!(
+            Shortcut::HighestGeneration.pick(&entries),
+            Some(Entity::from_raw(1))
+        );
+        assert_eq!(Shortcut::Oldest.pick(&[]), None);
+    }
+}
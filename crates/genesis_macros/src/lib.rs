@@ -8,6 +8,8 @@ pub fn derive_behaviour_tracker(input: TokenStream2) -> TokenStream2 {
     let struct_name = &ast.ident;
     let (impl_generics, type_generics, where_clause) = &ast.generics.split_for_impl();
 
+    let name = struct_name.to_string();
+
     quote! {
         impl #impl_generics genesis_traits::BehaviourTracker for #struct_name #type_generics #where_clause {
             fn new() -> Self where Self: Sized {
@@ -27,6 +29,10 @@ pub fn derive_behaviour_tracker(input: TokenStream2) -> TokenStream2 {
                 self.sum -= floor;
                 floor as usize
             }
+
+            fn name(&self) -> &'static str {
+                #name
+            }
         }
     }
 }
@@ -100,6 +106,10 @@ mod tests {
                     self.sum -= floor;
                     floor as usize
                 }
+
+                fn name(&self) -> &'static str {
+                    "EatingSum"
+                }
             }
         };
 
@@ -8,8 +8,8 @@ use thiserror::Error;
 
 #[derive(Error, Debug)]
 pub enum GenesisNewTypeError {
-    #[error("Weight must be between -1 and 1.")]
-    InvalidWeight,
+    #[error("Weight must be between -{0} and {0}.")]
+    InvalidWeight(f32),
 
     #[error("Probability must be between 0 and 1.")]
     InvalidProbability,
@@ -41,32 +41,54 @@ impl<'de> Deserialize<'de> for Weight {
         D: Deserializer<'de>,
     {
         let w: f32 = Deserialize::deserialize(deserializer)?;
-        Self::new(w).map_err(Error::custom)
+        Self::within(w, MAX_WEIGHT_LIMIT).map_err(Error::custom)
     }
 }
 
 pub type Bias = Weight;
 
+/// The largest bound a config can put on a weight's magnitude, and so on a saved weight.
+pub const MAX_WEIGHT_LIMIT: f32 = 100.0;
+
 impl Weight {
     pub fn new(w: f32) -> Result<Self, GenesisNewTypeError> {
-        if !(-1_f32..=1_f32).contains(&w) {
-            return Err(GenesisNewTypeError::InvalidWeight);
+        Self::within(w, 1.0)
+    }
+
+    /// Like [`Weight::new`], but for weights bounded by a configured limit.
+    pub fn within(w: f32, limit: f32) -> Result<Self, GenesisNewTypeError> {
+        let limit = limit.clamp(0.0, MAX_WEIGHT_LIMIT);
+        if !(-limit..=limit).contains(&w) {
+            return Err(GenesisNewTypeError::InvalidWeight(limit));
         }
         Ok(Self(w))
     }
 
+    /// Clamps any value to within plus or minus `limit`.
+    #[must_use]
+    pub fn clamped(w: f32, limit: f32) -> Self {
+        let limit = limit.clamp(0.0, MAX_WEIGHT_LIMIT);
+        Self(w.clamp(-limit, limit))
+    }
+
     #[must_use]
     pub fn random() -> Self {
+        Self::random_within(1.0)
+    }
+
+    /// A uniformly random weight between plus and minus `limit`.
+    #[must_use]
+    pub fn random_within(limit: f32) -> Self {
         let mut rng = rand::thread_rng();
         let x: f32 = rng.gen();
-        let w = 2_f32.mul_add(x, -1_f32);
+        let w = 2_f32.mul_add(x, -1_f32) * limit.clamp(0.0, MAX_WEIGHT_LIMIT);
 
         Self(w)
     }
 
     #[must_use]
     pub fn abs(&self) -> Self {
-        Self::new(self.0.abs()).unwrap()
+        Self(self.0.abs())
     }
 
     #[must_use]
@@ -79,9 +101,7 @@ impl ops::Add for Weight {
     type Output = Self;
 
     fn add(self, rhs: Self) -> Self {
-        let result = self.0.add(rhs.0);
-
-        Self::new(result.clamp(-1.0, 1.0)).unwrap()
+        Self::clamped(self.0.add(rhs.0), 1.0)
     }
 }
 
@@ -89,9 +109,7 @@ impl ops::Sub for Weight {
     type Output = Self;
 
     fn sub(self, rhs: Self) -> Self {
-        let result = self.0.sub(rhs.0);
-
-        Self::new(result.clamp(-1.0, 1.0)).unwrap()
+        Self::clamped(self.0.sub(rhs.0), 1.0)
     }
 }
 
@@ -99,9 +117,7 @@ impl ops::Mul for Weight {
     type Output = Self;
 
     fn mul(self, rhs: Self) -> Self {
-        let result = self.0.mul(rhs.0);
-
-        Self::new(result.clamp(-1.0, 1.0)).unwrap()
+        Self::clamped(self.0.mul(rhs.0), 1.0)
     }
 }
 
@@ -109,9 +125,7 @@ impl ops::Div for Weight {
     type Output = Self;
 
     fn div(self, rhs: Self) -> Self {
-        let result = self.0.div(rhs.0);
-
-        Self::new(result.clamp(-1.0, 1.0)).unwrap()
+        Self::clamped(self.0.div(rhs.0), 1.0)
     }
 }
 
@@ -186,6 +200,25 @@ mod tests {
         assert!(-1.0 <= w.as_float() && 1.0 >= w.as_float());
     }
 
+    #[test]
+    fn random_weights_fill_the_given_limit() {
+        let w = util::Weight::random_within(5.0);
+
+        assert!(-5.0 <= w.as_float() && 5.0 >= w.as_float());
+    }
+
+    #[test]
+    fn weights_can_be_created_within_a_configured_limit() {
+        util::Weight::within(4.5, 5.0).unwrap();
+        assert!(util::Weight::within(5.5, 5.0).is_err());
+    }
+
+    #[test]
+    fn weights_are_clamped_to_the_given_limit() {
+        assert_eq!(util::Weight::clamped(7.0, 5.0).as_float(), 5.0);
+        assert_eq!(util::Weight::clamped(-7.0, 5.0).as_float(), -5.0);
+    }
+
     #[test]
     fn absolute_weight_returns_valid() {
         let w = util::Weight::new(-0.5).unwrap();
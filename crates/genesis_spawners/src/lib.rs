@@ -4,11 +4,14 @@ use std::f32::consts::PI;
 use anyhow::anyhow;
 use bevy_ecs::prelude::Resource;
 use derive_more::{Deref, DerefMut};
-use genesis_config::{DistributionConfig, SpawnerConfig};
+use genesis_config::{
+    DistributionConfig, SpawnerConfig, SpawnerMovementConfig, SpawnerRole, SpawnerScheduleConfig,
+};
 use genesis_maths::polars_to_cart;
-use glam::Vec3;
-use rand::{rngs::ThreadRng, Rng};
+use glam::{Vec2, Vec3};
+use rand::{rngs::ThreadRng, seq::SliceRandom, Rng};
 use rand_distr::{Distribution, Gamma, InverseGaussian, LogNormal, Normal, Uniform};
+use serde::{Deserialize, Serialize};
 
 pub enum DistributionKind {
     Gamma(Gamma<f32>),
@@ -42,10 +45,47 @@ impl DistributionKind {
     }
 }
 
+fn waypoint_position(path: &[Vec2], travelled: f32) -> Vec2 {
+    let segments: Vec<(Vec2, Vec2)> = path
+        .iter()
+        .zip(path.iter().cycle().skip(1))
+        .map(|(a, b)| (*a, *b))
+        .collect();
+    let total_length: f32 = segments.iter().map(|(a, b)| a.distance(*b)).sum();
+    if total_length == 0.0 {
+        return path[0];
+    }
+    let mut remaining = travelled % total_length;
+    for (start, end) in segments {
+        let length = start.distance(end);
+        if remaining <= length {
+            return start.lerp(end, remaining / length);
+        }
+        remaining -= length;
+    }
+    path[0]
+}
+
+/// The part of a spawner that changes as the simulation runs.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SpawnerState {
+    pub centre: (f32, f32),
+    pub active: bool,
+}
+
 pub struct Spawner {
+    origin: Vec3,
     centre: Vec3,
     radius: f32,
     dist: DistributionKind,
+    role: SpawnerRole,
+    plant_types: Vec<usize>,
+    plant_rate_limit: Option<f32>,
+    plant_allowance: f32,
+    movement: SpawnerMovementConfig,
+    schedule: Option<SpawnerScheduleConfig>,
+    active: bool,
+    last_update: f32,
     nearby_organisms: usize,
     nearby_food: usize,
 }
@@ -53,15 +93,24 @@ pub struct Spawner {
 impl Spawner {
     pub const fn new(centre: Vec3, radius: f32, dist: DistributionKind) -> Self {
         Self {
+            origin: centre,
             centre,
             radius,
             dist,
+            role: SpawnerRole::Both,
+            plant_types: vec![],
+            plant_rate_limit: None,
+            plant_allowance: 0.0,
+            movement: SpawnerMovementConfig::Fixed,
+            schedule: None,
+            active: true,
+            last_update: 0.0,
             nearby_organisms: 0,
             nearby_food: 0,
         }
     }
 
-    fn random_position(&self, rng: &mut ThreadRng) -> Vec3 {
+    pub fn random_position(&self, rng: &mut ThreadRng) -> Vec3 {
         let r = self.dist.sample(rng) * self.radius;
         let theta = rng.gen_range(-PI..PI);
         let (x, y) = polars_to_cart(r, theta);
@@ -73,7 +122,111 @@ impl Spawner {
         let centre = Vec3::new(x, y, 0.0);
         let dist = DistributionKind::from_config(&config.dist)?;
 
-        Ok(Self::new(centre, config.radius, dist))
+        let mut spawner = Self::new(centre, config.radius, dist);
+        spawner.role = config.role;
+        spawner.plant_types = config.species.clone();
+        spawner.plant_rate_limit = config.plant_rate_limit;
+        spawner.plant_allowance = config.plant_rate_limit.unwrap_or(0.0).max(1.0);
+        spawner.movement = config.movement.clone();
+        spawner.schedule = config.schedule.clone();
+        spawner.update(0.0);
+        Ok(spawner)
+    }
+
+    /// Moves the spawner along its path and applies its schedule at the given simulation time.
+    pub fn update(&mut self, elapsed_secs: f32) {
+        if let Some(rate_limit) = self.plant_rate_limit {
+            let delta = (elapsed_secs - self.last_update).max(0.0);
+            self.plant_allowance = rate_limit
+                .mul_add(delta, self.plant_allowance)
+                .min(rate_limit.max(1.0));
+        }
+        self.last_update = elapsed_secs;
+
+        let origin = self.origin.truncate();
+        let offset = match &self.movement {
+            SpawnerMovementConfig::Fixed => Vec2::ZERO,
+            SpawnerMovementConfig::Orbit { radius, period } => {
+                let theta = 2.0 * PI * elapsed_secs / period;
+                let (x, y) = polars_to_cart(*radius, theta);
+                Vec2::new(x, y)
+            }
+            SpawnerMovementConfig::Drift {
+                direction,
+                speed,
+                distance,
+            } => {
+                let direction = Vec2::new(direction.0, direction.1).normalize_or_zero();
+                let travelled = (speed * elapsed_secs) % (2.0 * distance).max(f32::EPSILON);
+                // Drift out to the full distance then back again.
+                let along = if travelled > *distance {
+                    2.0f32.mul_add(*distance, -travelled)
+                } else {
+                    travelled
+                };
+                direction * along
+            }
+            SpawnerMovementConfig::Waypoints { points, speed } => {
+                let mut path = vec![origin];
+                path.extend(points.iter().map(|(x, y)| Vec2::new(*x, *y)));
+                waypoint_position(&path, speed * elapsed_secs) - origin
+            }
+        };
+        self.centre = (origin + offset).extend(self.origin.z);
+
+        if let Some(schedule) = &self.schedule {
+            let cycle_progress = ((elapsed_secs + schedule.offset) / schedule.period).fract();
+            self.active = cycle_progress < schedule.active_fraction;
+        }
+    }
+
+    pub const fn centre(&self) -> Vec3 {
+        self.centre
+    }
+
+    pub const fn radius(&self) -> f32 {
+        self.radius
+    }
+
+    pub const fn is_active(&self) -> bool {
+        self.active
+    }
+
+    pub const fn role(&self) -> SpawnerRole {
+        self.role
+    }
+
+    /// Picks which type of plant to spawn next, defaulting to the main plant type.
+    pub fn random_plant_type(&self, rng: &mut ThreadRng) -> usize {
+        self.plant_types.choose(rng).copied().unwrap_or(0)
+    }
+
+    pub fn can_spawn_plant(&self) -> bool {
+        self.active
+            && self.role.spawns_plants()
+            && (self.plant_rate_limit.is_none() || self.plant_allowance >= 1.0)
+    }
+
+    pub fn can_spawn_bug(&self) -> bool {
+        self.active && self.role.spawns_bugs()
+    }
+
+    pub fn record_plant_spawn(&mut self) {
+        if self.plant_rate_limit.is_some() {
+            self.plant_allowance -= 1.0;
+        }
+    }
+
+    pub const fn state(&self) -> SpawnerState {
+        SpawnerState {
+            centre: (self.centre.x, self.centre.y),
+            active: self.active,
+        }
+    }
+
+    pub fn set_state(&mut self, state: &SpawnerState) {
+        self.centre = Vec3::new(state.centre.0, state.centre.1, self.origin.z);
+        self.active = state.active;
     }
 
     pub const fn nearby_organisms(&self) -> usize {
@@ -101,40 +254,74 @@ impl Spawner {
 pub struct Spawners(Vec<Spawner>);
 
 impl Spawners {
-    pub fn random_organism_position(&self, rng: &mut ThreadRng) -> Vec3 {
-        let index = self
-            .nearby_organisms()
-            .iter()
-            .enumerate()
-            .min_by(|(_, a), (_, b)| a.cmp(b))
-            .map(|(index, _)| index)
-            .unwrap();
+    pub fn random_organism_position(&self, rng: &mut ThreadRng) -> Option<Vec3> {
+        self.iter()
+            .filter(|s| s.can_spawn_bug())
+            .min_by_key(|s| s.nearby_organisms())
+            .map(|spawner| spawner.random_position(rng))
+    }
 
-        let spawner = &self.0[index];
-        spawner.random_position(rng)
+    /// The least crowded spawner that is currently able to spawn a plant.
+    pub fn food_spawner(&mut self) -> Option<&mut Spawner> {
+        self.iter_mut()
+            .filter(|s| s.can_spawn_plant())
+            .min_by_key(|s| s.nearby_food())
     }
 
-    pub fn random_food_position(&self, rng: &mut ThreadRng) -> Vec3 {
-        let index = self
-            .nearby_food()
-            .iter()
+    fn nearest(&self, position: &Vec3, role_filter: impl Fn(SpawnerRole) -> bool) -> Option<usize> {
+        self.iter()
             .enumerate()
-            .min_by(|(_, a), (_, b)| a.cmp(b))
+            .filter(|(_, s)| role_filter(s.role()))
+            .min_by(|(_, a), (_, b)| a.distance(position).total_cmp(&b.distance(position)))
             .map(|(index, _)| index)
-            .unwrap();
+    }
 
-        let spawner = &self.0[index];
-        spawner.random_position(rng)
+    pub fn nearest_bug_spawner(&self, position: &Vec3) -> Option<usize> {
+        self.nearest(position, SpawnerRole::spawns_bugs)
     }
 
-    pub fn from_configs(configs: &[SpawnerConfig]) -> anyhow::Result<Self> {
+    pub fn nearest_plant_spawner(&self, position: &Vec3) -> Option<usize> {
+        self.nearest(position, SpawnerRole::spawns_plants)
+    }
+
+    /// Creates the spawners, assigning plant type ids in the same order as
+    /// `WorldConfig::plant_types`. Spawners without a plant override or species list spawn
+    /// every one of the `shared_plant_types`.
+    pub fn from_configs(
+        configs: &[SpawnerConfig],
+        shared_plant_types: usize,
+    ) -> anyhow::Result<Self> {
         let mut result = vec![];
+        let mut next_override_type = shared_plant_types;
         for config in configs {
-            result.push(Spawner::from_config(config)?);
+            let mut spawner = Spawner::from_config(config)?;
+            if config.plant.is_some() {
+                spawner.plant_types = vec![next_override_type];
+                next_override_type += 1;
+            } else if spawner.plant_types.is_empty() {
+                spawner.plant_types = (0..shared_plant_types).collect();
+            }
+            result.push(spawner);
         }
         Ok(Self(result))
     }
 
+    pub fn update(&mut self, elapsed_secs: f32) {
+        for spawner in self.iter_mut() {
+            spawner.update(elapsed_secs);
+        }
+    }
+
+    pub fn states(&self) -> Vec<SpawnerState> {
+        self.iter().map(Spawner::state).collect()
+    }
+
+    pub fn set_states(&mut self, states: &[SpawnerState]) {
+        for (spawner, state) in self.iter_mut().zip(states) {
+            spawner.set_state(state);
+        }
+    }
+
     pub fn nearby_organisms(&self) -> Vec<usize> {
         self.0.iter().map(Spawner::nearby_organisms).collect()
     }
@@ -144,6 +331,150 @@ impl Spawners {
     }
 
     pub fn space_for_organisms(&self, min_number: usize) -> bool {
-        self.iter().any(|s| s.nearby_organisms() < min_number)
+        self.iter()
+            .any(|s| s.can_spawn_bug() && s.nearby_organisms() < min_number)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use genesis_config::{DistributionConfig, PlantConfig};
+
+    use super::*;
+
+    fn spawner_with(
+        movement: SpawnerMovementConfig,
+        schedule: Option<SpawnerScheduleConfig>,
+    ) -> Spawner {
+        let dist = DistributionConfig::new("uniform".to_string(), 0.0, 1.0);
+        let mut config = SpawnerConfig::new((100.0, 0.0), 50.0, dist);
+        config.movement = movement;
+        config.schedule = schedule;
+        Spawner::from_config(&config).unwrap()
+    }
+
+    #[test]
+    fn orbiting_spawner_returns_to_start() {
+        let mut spawner = spawner_with(
+            SpawnerMovementConfig::Orbit {
+                radius: 10.0,
+                period: 20.0,
+            },
+            None,
+        );
+        assert!(spawner.centre().distance(Vec3::new(110.0, 0.0, 0.0)) < 1e-3);
+        spawner.update(10.0);
+        assert!(spawner.centre().distance(Vec3::new(90.0, 0.0, 0.0)) < 1e-3);
+        spawner.update(20.0);
+        assert!(spawner.centre().distance(Vec3::new(110.0, 0.0, 0.0)) < 1e-3);
+    }
+
+    #[test]
+    fn drifting_spawner_turns_back() {
+        let mut spawner = spawner_with(
+            SpawnerMovementConfig::Drift {
+                direction: (0.0, 2.0),
+                speed: 10.0,
+                distance: 100.0,
+            },
+            None,
+        );
+        spawner.update(5.0);
+        assert_eq!(spawner.centre(), Vec3::new(100.0, 50.0, 0.0));
+        spawner.update(15.0);
+        assert_eq!(spawner.centre(), Vec3::new(100.0, 50.0, 0.0));
+        spawner.update(20.0);
+        assert_eq!(spawner.centre(), Vec3::new(100.0, 0.0, 0.0));
+    }
+
+    #[test]
+    fn waypoint_spawner_follows_path() {
+        let mut spawner = spawner_with(
+            SpawnerMovementConfig::Waypoints {
+                points: vec![(200.0, 0.0), (200.0, 100.0)],
+                speed: 10.0,
+            },
+            None,
+        );
+        spawner.update(5.0);
+        assert_eq!(spawner.centre(), Vec3::new(150.0, 0.0, 0.0));
+        spawner.update(15.0);
+        assert_eq!(spawner.centre(), Vec3::new(200.0, 50.0, 0.0));
+    }
+
+    #[test]
+    fn scheduled_spawner_switches_off() {
+        let schedule = SpawnerScheduleConfig {
+            period: 10.0,
+            active_fraction: 0.5,
+            offset: 0.0,
+        };
+        let mut spawners = Spawners(vec![spawner_with(
+            SpawnerMovementConfig::Fixed,
+            Some(schedule),
+        )]);
+        let mut rng = rand::thread_rng();
+
+        spawners.update(2.0);
+        assert!(spawners.food_spawner().is_some());
+        assert!(spawners.random_organism_position(&mut rng).is_some());
+        spawners.update(7.0);
+        assert!(spawners.food_spawner().is_none());
+        assert!(spawners.random_organism_position(&mut rng).is_none());
+        assert!(!spawners.space_for_organisms(10));
+    }
+
+    #[test]
+    fn spawners_respect_roles() {
+        let dist = DistributionConfig::new("uniform".to_string(), 0.0, 1.0);
+        let mut nursery = SpawnerConfig::new((0.0, 0.0), 10.0, dist.clone());
+        nursery.role = SpawnerRole::Bugs;
+        let mut garden = SpawnerConfig::new((1000.0, 0.0), 10.0, dist);
+        garden.role = SpawnerRole::Plants;
+        let mut spawners = Spawners::from_configs(&[nursery, garden], 1).unwrap();
+        let mut rng = rand::thread_rng();
+
+        let bug_position = spawners.random_organism_position(&mut rng).unwrap();
+        assert!(bug_position.length() <= 10.0);
+        let food_position = spawners.food_spawner().unwrap().random_position(&mut rng);
+        assert!(food_position.distance(Vec3::new(1000.0, 0.0, 0.0)) <= 10.0);
+
+        let far_away = Vec3::new(2000.0, 0.0, 0.0);
+        assert_eq!(spawners.nearest_bug_spawner(&far_away), Some(0));
+        assert_eq!(spawners.nearest_plant_spawner(&Vec3::ZERO), Some(1));
+    }
+
+    #[test]
+    fn spawners_get_plant_types() {
+        let dist = DistributionConfig::new("uniform".to_string(), 0.0, 1.0);
+        let shared = SpawnerConfig::new((0.0, 0.0), 10.0, dist.clone());
+        let mut overridden = SpawnerConfig::new((0.0, 0.0), 10.0, dist.clone());
+        overridden.plant = Some(PlantConfig::berry_bush());
+        let mut picky = SpawnerConfig::new((0.0, 0.0), 10.0, dist);
+        picky.species = vec![1];
+        let spawners = Spawners::from_configs(&[shared, overridden, picky], 2).unwrap();
+        let mut rng = rand::thread_rng();
+
+        assert!(spawners[0].random_plant_type(&mut rng) < 2);
+        assert_eq!(spawners[1].random_plant_type(&mut rng), 2);
+        assert_eq!(spawners[2].random_plant_type(&mut rng), 1);
+    }
+
+    #[test]
+    fn plant_rate_limit_refills_over_time() {
+        let dist = DistributionConfig::new("uniform".to_string(), 0.0, 1.0);
+        let mut config = SpawnerConfig::new((0.0, 0.0), 10.0, dist);
+        config.plant_rate_limit = Some(2.0);
+        let mut spawner = Spawner::from_config(&config).unwrap();
+
+        spawner.record_plant_spawn();
+        spawner.record_plant_spawn();
+        assert!(!spawner.can_spawn_plant());
+        spawner.update(0.5);
+        assert!(spawner.can_spawn_plant());
+        spawner.update(10.0);
+        spawner.record_plant_spawn();
+        spawner.record_plant_spawn();
+        assert!(!spawner.can_spawn_plant());
     }
 }
@@ -7,6 +7,8 @@ pub trait BehaviourTracker {
     fn add_time(&mut self, time: f32, cost: f32);
 
     fn uint_portion(&mut self) -> usize;
+
+    fn name(&self) -> &'static str;
 }
 
 #[bevy_trait_query::queryable]